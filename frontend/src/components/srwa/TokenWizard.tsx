@@ -18,7 +18,7 @@ import AdvancedSettings from './wizard/AdvancedSettings';
 import IntegrationSetup from './wizard/IntegrationSetup';
 import ReviewAndDeploy from './wizard/ReviewAndDeploy';
 
-import { TokenCreationForm, TokenTemplate, DeployedToken } from '@/types/srwa-contracts';
+import { TokenCreationForm, TokenTemplate, DeployedToken, emptySecurityMetadata } from '@/types/srwa-contracts';
 import { RWA_TEMPLATES } from '@/types/templates';
 
 const WIZARD_STEPS = [
@@ -86,6 +86,7 @@ export default function TokenWizard() {
     allowed_jurisdictions: [],
     denied_jurisdictions: [],
     initial_supply: '0',
+    security_metadata: emptySecurityMetadata(),
     initial_distribution: [],
     
     // Step 4: Integration
@@ -206,6 +207,7 @@ export default function TokenWizard() {
           max_holders: formData.max_holders,
           allowed_jurisdictions: formData.allowed_jurisdictions,
           denied_jurisdictions: formData.denied_jurisdictions,
+          security_metadata: formData.security_metadata,
         },
       };
 
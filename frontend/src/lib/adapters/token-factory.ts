@@ -4,7 +4,8 @@ import {
   TokenConfig, 
   DeployedToken, 
   TokenTemplate,
-  Address 
+  Address,
+  emptySecurityMetadata
 } from '@/types/srwa-contracts';
 
 // Contract IDs updated with CLI working IDs
@@ -37,6 +38,7 @@ export async function deployToken(formData: TokenCreationForm): Promise<Deployed
       max_holders: formData.max_holders,
       allowed_jurisdictions: formData.allowed_jurisdictions,
       denied_jurisdictions: formData.denied_jurisdictions,
+      security_metadata: formData.security_metadata,
     };
 
     // Generate deterministic salt for contract deployment
@@ -111,6 +113,7 @@ export async function deployTokenWithTemplate(
         trusted_issuers: [],
         allowed_jurisdictions: [],
         denied_jurisdictions: [],
+        security_metadata: emptySecurityMetadata(),
       },
     };
 
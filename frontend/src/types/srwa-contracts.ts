@@ -21,8 +21,32 @@ export interface TokenConfig {
   max_holders?: number;
   allowed_jurisdictions: string[];
   denied_jurisdictions: string[];
+  security_metadata: SecurityMetadata;
 }
 
+// Dados de referência do título (espelha SecurityMetadata do SrwaToken)
+export interface SecurityMetadata {
+  isin: string; // 12 caracteres ou vazio
+  cusip: string; // 9 caracteres ou vazio
+  issuer_lei: string; // 20 caracteres ou vazio
+  asset_class: string;
+  issuer_jurisdiction: string;
+  governing_law: string;
+  issue_date: number; // u64, segundos unix
+  maturity_date?: number;
+}
+
+// Metadados em branco, como nos templates do Factory
+export const emptySecurityMetadata = (): SecurityMetadata => ({
+  isin: "",
+  cusip: "",
+  issuer_lei: "",
+  asset_class: "",
+  issuer_jurisdiction: "",
+  governing_law: "",
+  issue_date: Math.floor(Date.now() / 1000),
+});
+
 // Trusted Issuer
 export interface TrustedIssuer {
   issuer: Address;
@@ -245,6 +269,7 @@ export interface TokenCreationForm {
   allowed_jurisdictions: string[];
   denied_jurisdictions: string[];
   initial_supply: string;
+  security_metadata: SecurityMetadata;
   initial_distribution: Array<{
     to: Address;
     amount: string;
@@ -1,10 +1,27 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, symbol_short, Address, Env, String, Symbol, Vec
+    contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec
 };
 
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const TOPICS: Symbol = symbol_short!("TOPICS");
+const TOPIC_CONFIG: Symbol = symbol_short!("TOPIC_CFG");
+const TOPIC_LIST: Symbol = symbol_short!("TOPIC_LST");
+const REQUIRED_TOPICS: Symbol = symbol_short!("REQ_TOPIC");
+const REQUIRED_TOKENS: Symbol = symbol_short!("REQ_TOKS");
+
+// Durations are in seconds; 0 means no default / no maximum / no grace.
+// Claims keep verifying for grace_period after they expire
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TopicConfig {
+    pub name: String,
+    pub schema_hash: BytesN<32>,
+    pub default_validity: u64,
+    pub max_validity: u64,
+    pub required_issuers: u32,
+    pub grace_period: u64,
+}
 
 #[contract]
 pub struct ClaimTopicsRegistry;
@@ -24,6 +41,12 @@ impl ClaimTopicsRegistry {
         let key = (TOPICS, topic_id);
         env.storage().persistent().set(&key, &topic_name);
 
+        let mut topics = Self::list_claim_topics(env.clone());
+        if !topics.contains(topic_id) {
+            topics.push_back(topic_id);
+            env.storage().instance().set(&TOPIC_LIST, &topics);
+        }
+
         env.events().publish(
             (symbol_short!("TOPIC_ADD"),),
             (topic_id, topic_name)
@@ -37,6 +60,28 @@ impl ClaimTopicsRegistry {
         if env.storage().persistent().has(&key) {
             let topic_name: String = env.storage().persistent().get(&key).unwrap();
             env.storage().persistent().remove(&key);
+            env.storage().persistent().remove(&(TOPIC_CONFIG, topic_id));
+
+            let mut topics = Self::list_claim_topics(env.clone());
+            if let Some(index) = topics.first_index_of(topic_id) {
+                topics.remove(index);
+                env.storage().instance().set(&TOPIC_LIST, &topics);
+            }
+
+            // Tokens with their own set stop requiring the removed topic
+            for token in Self::required_tokens(&env).iter() {
+                let key = (REQUIRED_TOPICS, token.clone());
+                let mut required: Vec<u32> = env.storage().persistent().get(&key).unwrap();
+                if let Some(index) = required.first_index_of(topic_id) {
+                    required.remove(index);
+                    env.storage().persistent().set(&key, &required);
+
+                    env.events().publish(
+                        (symbol_short!("REQ_TOPIC"),),
+                        (token, required)
+                    );
+                }
+            }
 
             env.events().publish(
                 (symbol_short!("TOPIC_REM"),),
@@ -50,22 +95,105 @@ impl ClaimTopicsRegistry {
         env.storage().persistent().get(&key)
     }
 
+    pub fn set_topic_config(
+        env: Env,
+        topic_id: u32,
+        schema_hash: BytesN<32>,
+        default_validity: u64,
+        max_validity: u64,
+        required_issuers: u32,
+        grace_period: u64,
+    ) {
+        Self::require_admin(&env);
+
+        let name: String = env.storage().persistent().get(&(TOPICS, topic_id))
+            .unwrap_or_else(|| panic!("Topic not found"));
+        if required_issuers == 0 {
+            panic!("At least one issuer is required");
+        }
+        if max_validity > 0 && default_validity > max_validity {
+            panic!("Default validity exceeds maximum");
+        }
+
+        let config = TopicConfig {
+            name,
+            schema_hash,
+            default_validity,
+            max_validity,
+            required_issuers,
+            grace_period,
+        };
+        env.storage().persistent().set(&(TOPIC_CONFIG, topic_id), &config);
+
+        env.events().publish(
+            (symbol_short!("TOPIC_CFG"),),
+            (topic_id, config)
+        );
+    }
+
+    // Topics without an explicit config accept any validity from a single issuer
+    pub fn get_topic_config(env: Env, topic_id: u32) -> Option<TopicConfig> {
+        let name: String = env.storage().persistent().get(&(TOPICS, topic_id))?;
+        let config = env.storage().persistent().get(&(TOPIC_CONFIG, topic_id))
+            .unwrap_or(TopicConfig {
+                name: name.clone(),
+                schema_hash: BytesN::from_array(&env, &[0; 32]),
+                default_validity: 0,
+                max_validity: 0,
+                required_issuers: 1,
+                grace_period: 0,
+            });
+        Some(TopicConfig { name, ..config })
+    }
+
     pub fn has_claim_topic(env: Env, topic_id: u32) -> bool {
         let key = (TOPICS, topic_id);
         env.storage().persistent().has(&key)
     }
 
     pub fn list_claim_topics(env: Env) -> Vec<u32> {
-        // Note: In a real implementation, you'd need an index to efficiently query all topics
-        // For now, return hardcoded standard topics
-        let mut topics = Vec::new(&env);
-        topics.push_back(1); // KYC
-        topics.push_back(2); // AML
-        topics.push_back(3); // Accredited Investor
-        topics.push_back(4); // Residency
-        topics.push_back(5); // Sanctions Clear
-        topics.push_back(6); // PEP Check
-        topics
+        env.storage().instance().get(&TOPIC_LIST).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // Topics a token requires its holders to be verified for; tokens without
+    // their own set require every registered topic
+    pub fn set_required_topics(env: Env, token: Address, topics: Vec<u32>) {
+        Self::require_admin(&env);
+
+        for topic_id in topics.iter() {
+            if !Self::has_claim_topic(env.clone(), topic_id) {
+                panic!("Topic not found");
+            }
+        }
+
+        env.storage().persistent().set(&(REQUIRED_TOPICS, token.clone()), &topics);
+
+        let mut tokens = Self::required_tokens(&env);
+        if !tokens.contains(&token) {
+            tokens.push_back(token.clone());
+            env.storage().instance().set(&REQUIRED_TOKENS, &tokens);
+        }
+
+        env.events().publish(
+            (symbol_short!("REQ_TOPIC"),),
+            (token, topics)
+        );
+    }
+
+    pub fn clear_required_topics(env: Env, token: Address) {
+        Self::require_admin(&env);
+        env.storage().persistent().remove(&(REQUIRED_TOPICS, token.clone()));
+
+        let mut tokens = Self::required_tokens(&env);
+        if let Some(index) = tokens.first_index_of(&token) {
+            tokens.remove(index);
+            env.storage().instance().set(&REQUIRED_TOKENS, &tokens);
+        }
+    }
+
+    pub fn get_required_topics(env: Env, token: Address) -> Vec<u32> {
+        env.storage().persistent().get(&(REQUIRED_TOPICS, token))
+            .unwrap_or_else(|| Self::list_claim_topics(env))
     }
 
     pub fn set_admin(env: Env, new_admin: Address) {
@@ -77,6 +205,11 @@ impl ClaimTopicsRegistry {
         env.storage().instance().get(&ADMIN).unwrap()
     }
 
+    // Tokens holding their own required topic set
+    fn required_tokens(env: &Env) -> Vec<Address> {
+        env.storage().instance().get(&REQUIRED_TOKENS).unwrap_or_else(|| Vec::new(env))
+    }
+
     fn require_admin(env: &Env) {
         let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
         admin.require_auth();
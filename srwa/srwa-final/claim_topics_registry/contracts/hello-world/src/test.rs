@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env, String};
 
 #[test]
 fn test_claim_topics_registry() {
@@ -29,7 +29,7 @@ fn test_claim_topics_registry() {
 
     // List topics should include our topic
     let topics = client.list_claim_topics();
-    assert!(topics.contains(&topic_id));
+    assert!(topics.contains(topic_id));
 
     // Remove topic
     client.remove_claim_topic(&topic_id);
@@ -37,4 +37,82 @@ fn test_claim_topics_registry() {
     // Check topic no longer exists
     assert!(!client.has_claim_topic(&topic_id));
     assert!(client.get_claim_topic(&topic_id).is_none());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_topic_config() {
+    let env = Env::default();
+    let contract_id = env.register(ClaimTopicsRegistry, ());
+    let client = ClaimTopicsRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    env.mock_all_auths();
+
+    let topic_id = 3u32;
+    let topic_name = String::from_str(&env, "Accredited Investor");
+    client.add_claim_topic(&topic_id, &topic_name);
+
+    // Unconfigured topics need a single issuer and have no validity bounds
+    let config = client.get_topic_config(&topic_id).unwrap();
+    assert_eq!(config.required_issuers, 1);
+    assert_eq!(config.max_validity, 0);
+
+    let schema_hash = BytesN::from_array(&env, &[7; 32]);
+    client.set_topic_config(&topic_id, &schema_hash, &(86400u64 * 90), &(86400u64 * 365), &2u32, &(86400u64 * 14));
+
+    let config = client.get_topic_config(&topic_id).unwrap();
+    assert_eq!(config.name, topic_name);
+    assert_eq!(config.schema_hash, schema_hash);
+    assert_eq!(config.default_validity, 86400 * 90);
+    assert_eq!(config.required_issuers, 2);
+    assert_eq!(config.grace_period, 86400 * 14);
+
+    // Invalid configs and unknown topics are rejected
+    assert!(client.try_set_topic_config(&topic_id, &schema_hash, &(86400u64 * 400), &(86400u64 * 365), &1u32, &0u64).is_err());
+    assert!(client.try_set_topic_config(&topic_id, &schema_hash, &0u64, &0u64, &0u32, &0u64).is_err());
+    assert!(client.try_set_topic_config(&9u32, &schema_hash, &0u64, &0u64, &1u32, &0u64).is_err());
+
+    client.remove_claim_topic(&topic_id);
+    assert!(client.get_topic_config(&topic_id).is_none());
+}
+
+#[test]
+fn test_topic_index_and_required_topics() {
+    let env = Env::default();
+    let contract_id = env.register(ClaimTopicsRegistry, ());
+    let client = ClaimTopicsRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let token = Address::generate(&env);
+    client.initialize(&admin);
+    env.mock_all_auths();
+
+    assert_eq!(client.list_claim_topics().len(), 0);
+
+    client.add_claim_topic(&1u32, &String::from_str(&env, "KYC"));
+    client.add_claim_topic(&2u32, &String::from_str(&env, "AML"));
+    client.add_claim_topic(&101u32, &String::from_str(&env, "Qualified Purchaser"));
+    client.add_claim_topic(&101u32, &String::from_str(&env, "Qualified Purchaser"));
+    assert_eq!(client.list_claim_topics(), vec![&env, 1u32, 2u32, 101u32]);
+
+    client.remove_claim_topic(&2u32);
+    assert_eq!(client.list_claim_topics(), vec![&env, 1u32, 101u32]);
+
+    // Tokens default to every topic until given their own set
+    assert_eq!(client.get_required_topics(&token), vec![&env, 1u32, 101u32]);
+
+    client.set_required_topics(&token, &vec![&env, 101u32]);
+    assert_eq!(client.get_required_topics(&token), vec![&env, 101u32]);
+    assert!(client.try_set_required_topics(&token, &vec![&env, 2u32]).is_err());
+
+    // Removing a topic drops it from every token's own set
+    let other_token = Address::generate(&env);
+    client.set_required_topics(&other_token, &vec![&env, 1u32, 101u32]);
+    client.remove_claim_topic(&101u32);
+    assert_eq!(client.get_required_topics(&token).len(), 0);
+    assert_eq!(client.get_required_topics(&other_token), vec![&env, 1u32]);
+
+    client.clear_required_topics(&token);
+    assert_eq!(client.get_required_topics(&token), vec![&env, 1u32]);
+}
@@ -1,13 +1,21 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Bytes, Env, IntoVal, Symbol, Vec
 };
 
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const IDENTITY_REGISTRY: Symbol = symbol_short!("ID_REG");
+const CLAIM_TOPICS_REGISTRY: Symbol = symbol_short!("CLM_TOPIC");
 const MODULES: Symbol = symbol_short!("MODULES");
 const BOUND_TOKENS: Symbol = symbol_short!("TOKENS");
+const MODULE_HOOKS: Symbol = symbol_short!("MOD_HOOKS");
 
+// Partition used by tokens for unrestricted balances
+const DEFAULT_PARTITION: Symbol = symbol_short!("DEFAULT");
+
+// from_balance, to_balance and total_supply are the values after the transfer
+// (for checks, the values it would leave), so modules never need to call back
+// into the token while it is executing
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct TransferContext {
@@ -15,6 +23,23 @@ pub struct TransferContext {
     pub to: Address,
     pub amount: i128,
     pub token: Address,
+    pub partition: Symbol,
+    pub memo: Option<Bytes>,
+    pub from_balance: i128,
+    pub to_balance: i128,
+    pub total_supply: i128,
+}
+
+// Entrypoints the core invokes on a module; None skips that hook. Modules
+// default to the ComplianceModule names (check, transferred, created,
+// destroyed); modules sharing a wasm use prefixed names instead.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ModuleHooks {
+    pub check: Option<Symbol>,
+    pub transferred: Option<Symbol>,
+    pub created: Option<Symbol>,
+    pub destroyed: Option<Symbol>,
 }
 
 #[contract]
@@ -100,26 +125,76 @@ impl ComplianceCore {
         env.storage().instance().get(&MODULES).unwrap_or_else(|| Vec::new(&env))
     }
 
+    pub fn set_module_hooks(env: Env, module: Address, hooks: ModuleHooks) {
+        Self::require_admin(&env);
+
+        let key = (MODULE_HOOKS, module.clone());
+        env.storage().persistent().set(&key, &hooks);
+
+        env.events().publish(
+            (symbol_short!("MOD_HOOKS"),),
+            (module, hooks)
+        );
+    }
+
+    pub fn get_module_hooks(env: Env, module: Address) -> ModuleHooks {
+        let key = (MODULE_HOOKS, module);
+        env.storage().persistent().get(&key).unwrap_or_else(|| ModuleHooks {
+            check: Some(Symbol::new(&env, "check")),
+            transferred: Some(Symbol::new(&env, "transferred")),
+            created: Some(Symbol::new(&env, "created")),
+            destroyed: Some(Symbol::new(&env, "destroyed")),
+        })
+    }
+
+    // Reads the parties' balances and the supply from the token, so it is for
+    // callers outside the token's own invocation (simulations, integrations).
+    // Tokens check their transfers with can_transfer_with_context, since
+    // Soroban does not allow the token to be re-entered.
     pub fn can_transfer(env: Env, from: Address, to: Address, amount: i128, token: Address) -> bool {
+        let total_supply = Self::token_total_supply(&env, &token);
+        let (from_balance, to_balance) = if from == to {
+            let balance = Self::token_balance(&env, &token, &from);
+            (balance, balance)
+        } else {
+            (
+                Self::token_balance(&env, &token, &from) - amount,
+                Self::token_balance(&env, &token, &to) + amount,
+            )
+        };
+
+        let context = TransferContext {
+            from,
+            to,
+            amount,
+            token,
+            partition: DEFAULT_PARTITION,
+            memo: None,
+            from_balance,
+            to_balance,
+            total_supply,
+        };
+        Self::can_transfer_with_context(env, context)
+    }
+
+    // The token supplies the balances the transfer would leave
+    pub fn can_transfer_with_context(env: Env, context: TransferContext) -> bool {
         // Check if token is bound to this compliance contract
-        if !Self::is_token_bound(env.clone(), token.clone()) {
+        if !Self::is_token_bound(env.clone(), context.token.clone()) {
             return false;
         }
 
         // Check basic identity verification
         let identity_registry: Address = env.storage().instance().get(&IDENTITY_REGISTRY).unwrap();
-        if !Self::is_verified(&env, &identity_registry, &from) || !Self::is_verified(&env, &identity_registry, &to) {
+        let required_topics = Self::required_topics(&env, &context.token);
+        if !Self::is_verified(&env, &identity_registry, &context.from, &required_topics)
+            || !Self::is_verified(&env, &identity_registry, &context.to, &required_topics)
+        {
             return false;
         }
 
         // Check all enabled modules
         let modules = Self::get_enabled_modules(env.clone());
-        let context = TransferContext {
-            from: from.clone(),
-            to: to.clone(),
-            amount,
-            token: token.clone(),
-        };
 
         for module in modules.iter() {
             if !Self::check_module(&env, &module, &context) {
@@ -130,32 +205,40 @@ impl ComplianceCore {
         true
     }
 
-    pub fn transferred(env: Env, from: Address, to: Address, amount: i128, token: Address) {
+    pub fn transferred_with_context(env: Env, context: TransferContext) {
+        // Only the token itself reports its transfers; modules trust this call
+        context.token.require_auth();
+
         // Notify all modules about the completed transfer
         let modules = Self::get_enabled_modules(env.clone());
-        let context = TransferContext {
-            from: from.clone(),
-            to: to.clone(),
-            amount,
-            token: token.clone(),
-        };
 
         for module in modules.iter() {
             Self::notify_module_transferred(&env, &module, &context);
         }
 
-        env.events().publish(
-            (symbol_short!("TRANSFER"),),
-            (from, to, amount, token)
-        );
+        let TransferContext { from, to, amount, token, memo, .. } = context;
+        match memo {
+            Some(memo) => env.events().publish(
+                (symbol_short!("TRANSFER"), symbol_short!("MEMO")),
+                (from, to, amount, token, memo)
+            ),
+            None => env.events().publish(
+                (symbol_short!("TRANSFER"),),
+                (from, to, amount, token)
+            ),
+        }
     }
 
-    pub fn created(env: Env, to: Address, amount: i128, token: Address) {
+    // `balance` and `total_supply` are the holder's balance and the token's
+    // supply after the mint or burn
+    pub fn created(env: Env, to: Address, amount: i128, token: Address, balance: i128, total_supply: i128) {
+        token.require_auth();
+
         // Notify all modules about token creation (minting)
         let modules = Self::get_enabled_modules(env.clone());
 
         for module in modules.iter() {
-            Self::notify_module_created(&env, &module, &to, amount, &token);
+            Self::notify_module_created(&env, &module, &to, amount, &token, balance, total_supply);
         }
 
         env.events().publish(
@@ -164,12 +247,14 @@ impl ComplianceCore {
         );
     }
 
-    pub fn destroyed(env: Env, from: Address, amount: i128, token: Address) {
+    pub fn destroyed(env: Env, from: Address, amount: i128, token: Address, balance: i128, total_supply: i128) {
+        token.require_auth();
+
         // Notify all modules about token destruction (burning)
         let modules = Self::get_enabled_modules(env.clone());
 
         for module in modules.iter() {
-            Self::notify_module_destroyed(&env, &module, &from, amount, &token);
+            Self::notify_module_destroyed(&env, &module, &from, amount, &token, balance, total_supply);
         }
 
         env.events().publish(
@@ -196,36 +281,115 @@ impl ComplianceCore {
         env.storage().instance().get(&IDENTITY_REGISTRY).unwrap()
     }
 
+    // When set, holders are verified against each token's required topic set
+    // instead of every registered topic
+    pub fn set_claim_topics_registry(env: Env, claim_topics_registry: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&CLAIM_TOPICS_REGISTRY, &claim_topics_registry);
+    }
+
+    pub fn get_claim_topics_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&CLAIM_TOPICS_REGISTRY)
+    }
+
     fn require_admin(env: &Env) {
         let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
         admin.require_auth();
     }
 
-    fn is_verified(env: &Env, identity_registry: &Address, holder: &Address) -> bool {
-        // This would call the IdentityRegistry contract
-        // For now, simplified implementation
-        true
+    fn required_topics(env: &Env, token: &Address) -> Option<Vec<u32>> {
+        let claim_topics_registry: Address = env.storage().instance().get(&CLAIM_TOPICS_REGISTRY)?;
+        Some(env.invoke_contract(
+            &claim_topics_registry,
+            &Symbol::new(env, "get_required_topics"),
+            vec![env, token.into_val(env)],
+        ))
+    }
+
+    fn is_verified(env: &Env, identity_registry: &Address, holder: &Address, required_topics: &Option<Vec<u32>>) -> bool {
+        match required_topics {
+            Some(topics) => env.invoke_contract(
+                identity_registry,
+                &Symbol::new(env, "is_verified_for_topics"),
+                vec![env, holder.into_val(env), topics.into_val(env)],
+            ),
+            None => env.invoke_contract(
+                identity_registry,
+                &Symbol::new(env, "is_verified"),
+                vec![env, holder.into_val(env)],
+            ),
+        }
+    }
+
+    fn token_balance(env: &Env, token: &Address, holder: &Address) -> i128 {
+        env.invoke_contract(token, &Symbol::new(env, "balance"), vec![env, holder.into_val(env)])
+    }
+
+    fn token_total_supply(env: &Env, token: &Address) -> i128 {
+        env.invoke_contract(token, &Symbol::new(env, "total_supply"), vec![env])
     }
 
     fn check_module(env: &Env, module: &Address, context: &TransferContext) -> bool {
-        // This would call the module's check function
-        // For now, simplified implementation
-        true
+        match Self::get_module_hooks(env.clone(), module.clone()).check {
+            Some(hook) => env.invoke_contract(module, &hook, vec![env, context.into_val(env)]),
+            None => true,
+        }
     }
 
     fn notify_module_transferred(env: &Env, module: &Address, context: &TransferContext) {
-        // This would call the module's transferred function
-        // For now, simplified implementation
+        if let Some(hook) = Self::get_module_hooks(env.clone(), module.clone()).transferred {
+            env.invoke_contract::<()>(module, &hook, vec![env, context.into_val(env)]);
+        }
     }
 
-    fn notify_module_created(env: &Env, module: &Address, to: &Address, amount: i128, token: &Address) {
-        // This would call the module's created function
-        // For now, simplified implementation
+    fn notify_module_created(
+        env: &Env,
+        module: &Address,
+        to: &Address,
+        amount: i128,
+        token: &Address,
+        balance: i128,
+        total_supply: i128,
+    ) {
+        if let Some(hook) = Self::get_module_hooks(env.clone(), module.clone()).created {
+            env.invoke_contract::<()>(
+                module,
+                &hook,
+                vec![
+                    env,
+                    to.into_val(env),
+                    amount.into_val(env),
+                    token.into_val(env),
+                    balance.into_val(env),
+                    total_supply.into_val(env),
+                ],
+            );
+        }
     }
 
-    fn notify_module_destroyed(env: &Env, module: &Address, from: &Address, amount: i128, token: &Address) {
-        // This would call the module's destroyed function
-        // For now, simplified implementation
+    fn notify_module_destroyed(
+        env: &Env,
+        module: &Address,
+        from: &Address,
+        amount: i128,
+        token: &Address,
+        balance: i128,
+        total_supply: i128,
+    ) {
+        if let Some(hook) = Self::get_module_hooks(env.clone(), module.clone()).destroyed {
+            env.invoke_contract::<()>(
+                module,
+                &hook,
+                vec![
+                    env,
+                    from.into_val(env),
+                    amount.into_val(env),
+                    token.into_val(env),
+                    balance.into_val(env),
+                    total_supply.into_val(env),
+                ],
+            );
+        }
     }
 }
 
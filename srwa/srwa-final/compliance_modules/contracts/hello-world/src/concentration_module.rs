@@ -0,0 +1,196 @@
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, Address, Env, Symbol
+};
+use crate::{resolve_investor, TransferContext};
+
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const COMPLIANCE: Symbol = symbol_short!("COMPLY");
+const CONCENTRATION_LIMIT: Symbol = symbol_short!("CONC_LIM");
+const EXEMPT: Symbol = symbol_short!("CONC_EXMT");
+const IDENTITY_REGISTRY: Symbol = symbol_short!("CONC_REG");
+const WALLET_BALANCE: Symbol = symbol_short!("CONC_WBAL");
+const HOLDING: Symbol = symbol_short!("CONC_HOLD");
+
+const BASIS_POINTS: i128 = 10_000;
+
+// Caps any single holder at a share of the token's total supply, in basis
+// points. With a registry set, an investor's wallets are added up, so the cap
+// cannot be sidestepped by spreading a position across wallets; balances are
+// tracked from the hooks, so wallets funded before the module was enabled are
+// not counted. Exempt addresses such as the issuer treasury, lending pools and
+// market makers may hold any amount. Since the first mint makes its recipient
+// the whole supply, initial issuance should go to an exempt treasury.
+#[contract]
+pub struct ConcentrationModule;
+
+#[contractimpl]
+impl ConcentrationModule {
+    // The hooks only accept calls from the compliance contract
+    pub fn init_concentration(env: Env, admin: Address, compliance: Address) {
+        if env.storage().instance().has(&ADMIN) {
+            panic!("Already initialized");
+        }
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&COMPLIANCE, &compliance);
+    }
+
+    // 0 disables the limit
+    pub fn set_concentration_limit(env: Env, token: Address, limit_bps: u32) {
+        Self::require_admin(&env);
+        if limit_bps as i128 > BASIS_POINTS {
+            panic!("Limit exceeds 100%");
+        }
+
+        let key = (CONCENTRATION_LIMIT, token.clone());
+        env.storage().persistent().set(&key, &limit_bps);
+
+        env.events().publish(
+            (symbol_short!("CONC_SET"),),
+            (token, limit_bps)
+        );
+    }
+
+    pub fn get_concentration_limit(env: Env, token: Address) -> u32 {
+        let key = (CONCENTRATION_LIMIT, token);
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    // Holdings are aggregated per investor identity when a registry is set
+    pub fn set_concentration_registry(env: Env, token: Address, identity_registry: Address) {
+        Self::require_admin(&env);
+        let key = (IDENTITY_REGISTRY, token);
+        env.storage().persistent().set(&key, &identity_registry);
+    }
+
+    // Tracked balance across all wallets of the wallet's investor
+    pub fn get_investor_holding(env: Env, token: Address, wallet: Address) -> i128 {
+        let investor = Self::investor_of(&env, &token, &wallet);
+        Self::holding(&env, &token, &investor)
+    }
+
+    pub fn set_concentration_exempt(env: Env, token: Address, address: Address, exempt: bool) {
+        Self::require_admin(&env);
+        let key = (EXEMPT, token.clone(), address.clone());
+        if exempt {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events().publish(
+            (symbol_short!("CONC_EXMT"),),
+            (token, address, exempt)
+        );
+    }
+
+    pub fn is_concentration_exempt(env: Env, token: Address, address: Address) -> bool {
+        let key = (EXEMPT, token, address);
+        env.storage().persistent().get(&key).unwrap_or(false)
+    }
+
+    pub fn check_concentration(env: Env, context: TransferContext) -> bool {
+        let to_investor = Self::investor_of(&env, &context.token, &context.to);
+
+        // Moving between wallets of one investor leaves their holding unchanged
+        if Self::investor_of(&env, &context.token, &context.from) == to_investor {
+            return true;
+        }
+
+        let holding = Self::holding(&env, &context.token, &to_investor)
+            - Self::wallet_balance(&env, &context.token, &context.to)
+            + context.to_balance;
+        Self::within_limit(&env, &context.token, &context.to, &to_investor, holding, context.total_supply)
+    }
+
+    pub fn concentration_transferred(env: Env, context: TransferContext) {
+        Self::require_compliance(&env);
+
+        Self::update_wallet(&env, &context.token, &context.to, context.to_balance);
+        if context.from != context.to {
+            Self::update_wallet(&env, &context.token, &context.from, context.from_balance);
+        }
+    }
+
+    // Runs after the mint has been applied, so balance and supply already
+    // include it; panicking reverts the mint
+    pub fn concentration_created(env: Env, to: Address, amount: i128, token: Address, balance: i128, total_supply: i128) {
+        Self::require_compliance(&env);
+
+        if amount <= 0 {
+            return;
+        }
+
+        let investor = Self::update_wallet(&env, &token, &to, balance);
+        let holding = Self::holding(&env, &token, &investor);
+        if !Self::within_limit(&env, &token, &to, &investor, holding, total_supply) {
+            panic!("Concentration limit exceeded");
+        }
+    }
+
+    pub fn concentration_destroyed(env: Env, from: Address, _amount: i128, token: Address, balance: i128, _total_supply: i128) {
+        Self::require_compliance(&env);
+        Self::update_wallet(&env, &token, &from, balance);
+    }
+
+    // Either the wallet or the investor it belongs to can be exempt
+    fn within_limit(env: &Env, token: &Address, wallet: &Address, investor: &Address, holding: i128, supply: i128) -> bool {
+        let limit_bps = Self::get_concentration_limit(env.clone(), token.clone());
+        if limit_bps == 0
+            || Self::is_concentration_exempt(env.clone(), token.clone(), wallet.clone())
+            || Self::is_concentration_exempt(env.clone(), token.clone(), investor.clone())
+        {
+            return true;
+        }
+
+        holding * BASIS_POINTS <= supply * limit_bps as i128
+    }
+
+    // Records the wallet's new balance and moves the difference onto its
+    // investor's holding; returns the investor
+    fn update_wallet(env: &Env, token: &Address, wallet: &Address, balance: i128) -> Address {
+        let investor = Self::investor_of(env, token, wallet);
+        let previous = Self::wallet_balance(env, token, wallet);
+        let holding = Self::holding(env, token, &investor) - previous + balance;
+
+        let wallet_key = (WALLET_BALANCE, token.clone(), wallet.clone());
+        if balance > 0 {
+            env.storage().persistent().set(&wallet_key, &balance);
+        } else {
+            env.storage().persistent().remove(&wallet_key);
+        }
+
+        let holding_key = (HOLDING, token.clone(), investor.clone());
+        if holding > 0 {
+            env.storage().persistent().set(&holding_key, &holding);
+        } else {
+            env.storage().persistent().remove(&holding_key);
+        }
+
+        investor
+    }
+
+    fn wallet_balance(env: &Env, token: &Address, wallet: &Address) -> i128 {
+        let key = (WALLET_BALANCE, token.clone(), wallet.clone());
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    fn holding(env: &Env, token: &Address, investor: &Address) -> i128 {
+        let key = (HOLDING, token.clone(), investor.clone());
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    fn investor_of(env: &Env, token: &Address, wallet: &Address) -> Address {
+        let identity_registry: Option<Address> = env.storage().persistent().get(&(IDENTITY_REGISTRY, token.clone()));
+        resolve_investor(env, &identity_registry, wallet)
+    }
+
+    fn require_compliance(env: &Env) {
+        let compliance: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
+        compliance.require_auth();
+    }
+
+    fn require_admin(env: &Env) {
+        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        admin.require_auth();
+    }
+}
@@ -0,0 +1,231 @@
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Env, IntoVal, String, Symbol, Vec
+};
+use crate::TransferContext;
+
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const COMPLIANCE: Symbol = symbol_short!("COMPLY");
+const HOLDING_PERIOD: Symbol = symbol_short!("HOLD_PER");
+const JURISDICTION_PERIOD: Symbol = symbol_short!("HOLD_JUR");
+const IDENTITY_REGISTRY: Symbol = symbol_short!("HOLD_REG");
+const LOTS: Symbol = symbol_short!("LOTS");
+
+// Upper bound on open lots per holder; beyond it the two oldest lots merge
+const MAX_LOTS: u32 = 32;
+
+// Tokens acquired in one mint or transfer
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct HoldingLot {
+    pub amount: i128,
+    pub acquired_at: u64,
+}
+
+// Acquisition-based resale restrictions (Rule 144, Reg S distribution
+// compliance periods). Every incoming mint or transfer opens a lot; a lot can
+// be resold once the holder's holding period has elapsed since acquisition.
+// Balance not covered by a lot (held before the module tracked the token, or
+// from lots that have matured) is unrestricted. Sales use that untracked
+// balance first, then consume lots oldest first.
+#[contract]
+pub struct HoldingPeriodModule;
+
+#[contractimpl]
+impl HoldingPeriodModule {
+    // The hooks below only accept calls from the compliance contract
+    pub fn init_holding_period(env: Env, admin: Address, compliance: Address) {
+        if env.storage().instance().has(&ADMIN) {
+            panic!("Already initialized");
+        }
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&COMPLIANCE, &compliance);
+    }
+
+    // Default period in seconds for the token; 0 disables the restriction
+    pub fn set_holding_period(env: Env, token: Address, period: u64) {
+        Self::require_admin(&env);
+        let key = (HOLDING_PERIOD, token.clone());
+        env.storage().persistent().set(&key, &period);
+
+        env.events().publish(
+            (symbol_short!("HOLD_SET"),),
+            (token, period)
+        );
+    }
+
+    // Overrides the default for holders resident in `country`, e.g. 40 days
+    // for Reg S offshore holders
+    pub fn set_country_holding_period(env: Env, token: Address, country: String, period: u64) {
+        Self::require_admin(&env);
+        let key = (JURISDICTION_PERIOD, token.clone(), country.clone());
+        env.storage().persistent().set(&key, &period);
+
+        env.events().publish(
+            (symbol_short!("HOLD_JUR"),),
+            (token, country, period)
+        );
+    }
+
+    pub fn remove_country_holding_period(env: Env, token: Address, country: String) {
+        Self::require_admin(&env);
+        let key = (JURISDICTION_PERIOD, token, country);
+        env.storage().persistent().remove(&key);
+    }
+
+    // Holder countries are looked up in the token's IdentityRegistry
+    pub fn set_holding_period_registry(env: Env, token: Address, identity_registry: Address) {
+        Self::require_admin(&env);
+        let key = (IDENTITY_REGISTRY, token);
+        env.storage().persistent().set(&key, &identity_registry);
+    }
+
+    pub fn get_holding_period(env: Env, token: Address, holder: Address) -> u64 {
+        let registry: Option<Address> = env.storage().persistent().get(&(IDENTITY_REGISTRY, token.clone()));
+        if let Some(registry) = registry {
+            let country: Option<String> = env.invoke_contract(
+                &registry,
+                &Symbol::new(&env, "get_investor_country"),
+                vec![&env, holder.into_val(&env)],
+            );
+            if let Some(country) = country {
+                let key = (JURISDICTION_PERIOD, token.clone(), country);
+                if let Some(period) = env.storage().persistent().get::<_, u64>(&key) {
+                    return period;
+                }
+            }
+        }
+
+        env.storage().persistent().get(&(HOLDING_PERIOD, token)).unwrap_or(0)
+    }
+
+    pub fn get_lots(env: Env, token: Address, holder: Address) -> Vec<HoldingLot> {
+        let key = (LOTS, token, holder);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // Amount still inside the holding period
+    pub fn get_restricted_amount(env: Env, token: Address, holder: Address) -> i128 {
+        let period = Self::get_holding_period(env.clone(), token.clone(), holder.clone());
+        if period == 0 {
+            return 0;
+        }
+
+        let now = env.ledger().timestamp();
+        Self::get_lots(env, token, holder).iter()
+            .filter(|lot| lot.acquired_at.saturating_add(period) > now)
+            .map(|lot| lot.amount)
+            .sum()
+    }
+
+    // The sender's balance after the transfer must still cover its
+    // restricted lots
+    pub fn check_holding_period(env: Env, context: TransferContext) -> bool {
+        let restricted = Self::get_restricted_amount(env.clone(), context.token.clone(), context.from.clone());
+        restricted <= 0 || context.from_balance >= restricted
+    }
+
+    pub fn holding_transferred(env: Env, context: TransferContext) {
+        Self::require_compliance(&env);
+
+        if context.amount > 0 && context.from != context.to {
+            let balance_before = context.from_balance + context.amount;
+            Self::consume_lots(&env, &context.token, &context.from, context.amount, balance_before);
+            Self::open_lot(&env, &context.token, &context.to, context.amount);
+        }
+    }
+
+    pub fn holding_created(env: Env, to: Address, amount: i128, token: Address, _balance: i128, _total_supply: i128) {
+        Self::require_compliance(&env);
+
+        if amount > 0 {
+            Self::open_lot(&env, &token, &to, amount);
+        }
+    }
+
+    pub fn holding_destroyed(env: Env, from: Address, amount: i128, token: Address, balance: i128, _total_supply: i128) {
+        Self::require_compliance(&env);
+
+        if amount > 0 {
+            Self::consume_lots(&env, &token, &from, amount, balance + amount);
+        }
+    }
+
+    fn open_lot(env: &Env, token: &Address, holder: &Address, amount: i128) {
+        let now = env.ledger().timestamp();
+        let period = Self::get_holding_period(env.clone(), token.clone(), holder.clone());
+
+        // Matured lots are dropped: their amount becomes untracked balance
+        let mut lots = Vec::new(env);
+        for lot in Self::get_lots(env.clone(), token.clone(), holder.clone()).iter() {
+            if lot.acquired_at.saturating_add(period) > now {
+                lots.push_back(lot);
+            }
+        }
+
+        // Acquisitions in the same ledger second share a lot
+        match lots.last() {
+            Some(mut last) if last.acquired_at == now => {
+                last.amount += amount;
+                lots.set(lots.len() - 1, last);
+            }
+            _ => lots.push_back(HoldingLot { amount, acquired_at: now }),
+        }
+
+        // Merging into the newer lot keeps its later acquisition time, so
+        // nothing is released early
+        while lots.len() > MAX_LOTS {
+            let oldest = lots.pop_front().unwrap();
+            let mut next = lots.first().unwrap();
+            next.amount += oldest.amount;
+            lots.set(0, next);
+        }
+
+        Self::store_lots(env, token, holder, &lots);
+    }
+
+    // Untracked balance goes first, then lots oldest first
+    fn consume_lots(env: &Env, token: &Address, holder: &Address, amount: i128, balance_before: i128) {
+        let mut lots = Self::get_lots(env.clone(), token.clone(), holder.clone());
+
+        let tracked: i128 = lots.iter().map(|lot| lot.amount).sum();
+        let untracked = (balance_before - tracked).max(0);
+        let mut remaining = amount - amount.min(untracked);
+
+        while remaining > 0 {
+            let mut lot = match lots.first() {
+                Some(lot) => lot,
+                None => break,
+            };
+
+            if lot.amount <= remaining {
+                remaining -= lot.amount;
+                lots.pop_front();
+            } else {
+                lot.amount -= remaining;
+                lots.set(0, lot);
+                remaining = 0;
+            }
+        }
+
+        Self::store_lots(env, token, holder, &lots);
+    }
+
+    fn store_lots(env: &Env, token: &Address, holder: &Address, lots: &Vec<HoldingLot>) {
+        let key = (LOTS, token.clone(), holder.clone());
+        if lots.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, lots);
+        }
+    }
+
+    fn require_compliance(env: &Env) {
+        let compliance: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
+        compliance.require_auth();
+    }
+
+    fn require_admin(env: &Env) {
+        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        admin.require_auth();
+    }
+}
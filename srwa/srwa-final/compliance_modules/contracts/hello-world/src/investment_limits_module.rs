@@ -0,0 +1,267 @@
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Env, IntoVal, Symbol, Vec
+};
+use crate::{resolve_investor, TransferContext};
+
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const COMPLIANCE: Symbol = symbol_short!("COMPLY");
+const IDENTITY_REGISTRY: Symbol = symbol_short!("INV_REG");
+const TIERS: Symbol = symbol_short!("INV_TIER");
+const WINDOW: Symbol = symbol_short!("INV_WIN");
+const INFLOWS: Symbol = symbol_short!("INFLOWS");
+const EXEMPT: Symbol = symbol_short!("INV_EXMPT");
+
+const SECONDS_PER_DAY: u64 = 86_400;
+const DEFAULT_WINDOW_DAYS: u64 = 365;
+
+// Investors verified for `topic_id` may invest up to `limit` per window;
+// None means unlimited (e.g. accredited or professional investors)
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InvestmentTier {
+    pub topic_id: u32,
+    pub limit: Option<i128>,
+}
+
+// Inflows received on one day
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InflowRecord {
+    pub day: u64,
+    pub amount: i128,
+}
+
+// Investment caps for crowdfunding-style regimes (Reg CF, EU ECSP). Mints
+// and incoming transfers from other investors count towards the recipient
+// identity's cumulative inflows over a rolling window; sales do not reduce
+// them. The limit comes from the first tier whose claim topic the investor
+// holds, falling back to the default.
+#[contract]
+pub struct InvestmentLimitsModule;
+
+#[contractimpl]
+impl InvestmentLimitsModule {
+    // The hooks below only accept calls from the compliance contract
+    pub fn init_investment_limits(env: Env, admin: Address, compliance: Address) {
+        if env.storage().instance().has(&ADMIN) {
+            panic!("Already initialized");
+        }
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&COMPLIANCE, &compliance);
+    }
+
+    // Tier claims are checked against, and wallets resolved to identities
+    // through, the token's IdentityRegistry
+    pub fn set_investment_registry(env: Env, token: Address, identity_registry: Address) {
+        Self::require_admin(&env);
+        let key = (IDENTITY_REGISTRY, token);
+        env.storage().persistent().set(&key, &identity_registry);
+    }
+
+    // Tiers are evaluated in order, so list the most permissive first
+    pub fn set_investment_tiers(env: Env, token: Address, tiers: Vec<InvestmentTier>, default_limit: Option<i128>) {
+        Self::require_admin(&env);
+
+        for tier in tiers.iter() {
+            if tier.limit.unwrap_or(0) < 0 {
+                panic!("Negative limit");
+            }
+        }
+        if default_limit.unwrap_or(0) < 0 {
+            panic!("Negative limit");
+        }
+
+        let key = (TIERS, token.clone());
+        env.storage().persistent().set(&key, &(tiers, default_limit));
+
+        env.events().publish(
+            (symbol_short!("INV_TIER"),),
+            (token, default_limit)
+        );
+    }
+
+    pub fn get_investment_tiers(env: Env, token: Address) -> Option<(Vec<InvestmentTier>, Option<i128>)> {
+        let key = (TIERS, token);
+        env.storage().persistent().get(&key)
+    }
+
+    pub fn set_investment_window(env: Env, token: Address, days: u64) {
+        Self::require_admin(&env);
+        if days == 0 {
+            panic!("Window must be positive");
+        }
+
+        let key = (WINDOW, token.clone());
+        env.storage().persistent().set(&key, &days);
+
+        env.events().publish(
+            (symbol_short!("INV_WIN"),),
+            (token, days)
+        );
+    }
+
+    pub fn get_investment_window(env: Env, token: Address) -> u64 {
+        let key = (WINDOW, token);
+        env.storage().persistent().get(&key).unwrap_or(DEFAULT_WINDOW_DAYS)
+    }
+
+    // Exempt recipients, e.g. the issuer's treasury receiving mints, are
+    // neither limited nor have their inflows recorded
+    pub fn set_investment_exempt(env: Env, token: Address, address: Address, exempt: bool) {
+        Self::require_admin(&env);
+        let key = (EXEMPT, token.clone(), address.clone());
+        if exempt {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events().publish(
+            (symbol_short!("INV_EXMPT"),),
+            (token, address, exempt)
+        );
+    }
+
+    pub fn is_investment_exempt(env: Env, token: Address, address: Address) -> bool {
+        let key = (EXEMPT, token, address);
+        env.storage().persistent().get(&key).unwrap_or(false)
+    }
+
+    // None when no tiers are configured or the investor's tier is unlimited
+    pub fn get_investment_limit(env: Env, token: Address, wallet: Address) -> Option<i128> {
+        let (tiers, default_limit) = Self::get_investment_tiers(env.clone(), token.clone())?;
+        let identity_registry: Option<Address> = env.storage().persistent().get(&(IDENTITY_REGISTRY, token));
+
+        if let Some(registry) = identity_registry {
+            for tier in tiers.iter() {
+                let verified: bool = env.invoke_contract(
+                    &registry,
+                    &Symbol::new(&env, "is_verified_for_topics"),
+                    vec![&env, wallet.into_val(&env), vec![&env, tier.topic_id].into_val(&env)],
+                );
+                if verified {
+                    return tier.limit;
+                }
+            }
+        }
+
+        default_limit
+    }
+
+    // Cumulative inflows of the wallet's investor identity within the window
+    pub fn get_invested_amount(env: Env, token: Address, wallet: Address) -> i128 {
+        let investor = Self::investor_of(&env, &token, &wallet);
+        let first_day = Self::first_day_in_window(&env, &token);
+
+        Self::get_inflows(&env, &token, &investor).iter()
+            .filter(|record| record.day >= first_day)
+            .map(|record| record.amount)
+            .sum()
+    }
+
+    // Amount the investor can still receive; None means unlimited
+    pub fn remaining_headroom(env: Env, token: Address, wallet: Address) -> Option<i128> {
+        let limit = Self::get_investment_limit(env.clone(), token.clone(), wallet.clone())?;
+        let invested = Self::get_invested_amount(env, token, wallet);
+        Some((limit - invested).max(0))
+    }
+
+    pub fn check_investment_limit(env: Env, context: TransferContext) -> bool {
+        if Self::same_investor(&env, &context) || Self::exempt(&env, &context.token, &context.to) {
+            return true;
+        }
+
+        match Self::remaining_headroom(env, context.token, context.to) {
+            Some(headroom) => context.amount <= headroom,
+            None => true,
+        }
+    }
+
+    pub fn investment_transferred(env: Env, context: TransferContext) {
+        Self::require_compliance(&env);
+
+        if context.amount > 0
+            && !Self::same_investor(&env, &context)
+            && !Self::exempt(&env, &context.token, &context.to)
+        {
+            Self::record_inflow(&env, &context.token, &context.to, context.amount);
+        }
+    }
+
+    // Panicking reverts a mint that takes the investor over their limit
+    pub fn investment_created(env: Env, to: Address, amount: i128, token: Address, _balance: i128, _total_supply: i128) {
+        Self::require_compliance(&env);
+
+        if amount <= 0 || Self::exempt(&env, &token, &to) {
+            return;
+        }
+
+        Self::record_inflow(&env, &token, &to, amount);
+
+        if let Some(limit) = Self::get_investment_limit(env.clone(), token.clone(), to.clone()) {
+            if Self::get_invested_amount(env.clone(), token, to) > limit {
+                panic!("Investment limit exceeded");
+            }
+        }
+    }
+
+    // Either the wallet or the investor it belongs to can be exempt
+    fn exempt(env: &Env, token: &Address, wallet: &Address) -> bool {
+        Self::is_investment_exempt(env.clone(), token.clone(), wallet.clone())
+            || Self::is_investment_exempt(env.clone(), token.clone(), Self::investor_of(env, token, wallet))
+    }
+
+    fn same_investor(env: &Env, context: &TransferContext) -> bool {
+        Self::investor_of(env, &context.token, &context.from) == Self::investor_of(env, &context.token, &context.to)
+    }
+
+    fn first_day_in_window(env: &Env, token: &Address) -> u64 {
+        let today = env.ledger().timestamp() / SECONDS_PER_DAY;
+        let window = Self::get_investment_window(env.clone(), token.clone());
+        (today + 1).saturating_sub(window)
+    }
+
+    fn get_inflows(env: &Env, token: &Address, investor: &Address) -> Vec<InflowRecord> {
+        let key = (INFLOWS, token.clone(), investor.clone());
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn record_inflow(env: &Env, token: &Address, wallet: &Address, amount: i128) {
+        let investor = Self::investor_of(env, token, wallet);
+        let today = env.ledger().timestamp() / SECONDS_PER_DAY;
+        let first_day = Self::first_day_in_window(env, token);
+
+        // Drop days that have left the window
+        let mut inflows = Vec::new(env);
+        for record in Self::get_inflows(env, token, &investor).iter() {
+            if record.day >= first_day {
+                inflows.push_back(record);
+            }
+        }
+
+        match inflows.last() {
+            Some(mut last) if last.day == today => {
+                last.amount += amount;
+                inflows.set(inflows.len() - 1, last);
+            }
+            _ => inflows.push_back(InflowRecord { day: today, amount }),
+        }
+
+        env.storage().persistent().set(&(INFLOWS, token.clone(), investor), &inflows);
+    }
+
+    fn investor_of(env: &Env, token: &Address, wallet: &Address) -> Address {
+        let identity_registry: Option<Address> = env.storage().persistent().get(&(IDENTITY_REGISTRY, token.clone()));
+        resolve_investor(env, &identity_registry, wallet)
+    }
+
+    fn require_compliance(env: &Env) {
+        let compliance: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
+        compliance.require_auth();
+    }
+
+    fn require_admin(env: &Env) {
+        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        admin.require_auth();
+    }
+}
@@ -1,12 +1,56 @@
 use soroban_sdk::{
-    contract, contractimpl, symbol_short, Address, Bytes, Env, String, Symbol, Vec
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Env, IntoVal, String, Symbol, Vec
 };
-use crate::{TransferContext, ComplianceModule};
+use crate::TransferContext;
 
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const ALLOWED_JURISDICTIONS: Symbol = symbol_short!("ALLOW_JUR");
 const DENIED_JURISDICTIONS: Symbol = symbol_short!("DENY_JUR");
 const ENABLED_TOKENS: Symbol = symbol_short!("TOKENS");
+const IDENTITY_REGISTRY: Symbol = symbol_short!("JUR_REG");
+const ALLOW_UNKNOWN: Symbol = symbol_short!("JUR_UNKN");
+const COUNTRY_GROUPS: Symbol = symbol_short!("JUR_GROUP");
+const ALLOWED_GROUPS: Symbol = symbol_short!("ALLOW_GRP");
+const DENIED_GROUPS: Symbol = symbol_short!("DENY_GRP");
+const TRANSFER_RULES: Symbol = symbol_short!("JUR_RULE");
+const GROUP_REFS: Symbol = symbol_short!("GRP_REFS");
+
+// ISO 3166-1 alpha-2 officially assigned codes
+const ISO_3166_ALPHA2: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS", "BT", "BV", "BW", "BY", "BZ",
+    "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN", "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ",
+    "DE", "DJ", "DK", "DM", "DO", "DZ",
+    "EC", "EE", "EG", "EH", "ER", "ES", "ET",
+    "FI", "FJ", "FK", "FM", "FO", "FR",
+    "GA", "GB", "GD", "GE", "GF", "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY",
+    "HK", "HM", "HN", "HR", "HT", "HU",
+    "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT",
+    "JE", "JM", "JO", "JP",
+    "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ",
+    "LA", "LB", "LC", "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY",
+    "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK", "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ",
+    "NA", "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ",
+    "OM",
+    "PA", "PE", "PF", "PG", "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY",
+    "QA",
+    "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS", "ST", "SV", "SX", "SY", "SZ",
+    "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO", "TR", "TT", "TV", "TW", "TZ",
+    "UA", "UG", "UM", "US", "UY", "UZ",
+    "VA", "VC", "VE", "VG", "VI", "VN", "VU",
+    "WF", "WS",
+    "YE", "YT",
+    "ZA", "ZM", "ZW",
+];
+
+// Destinations a holder from a given country may transfer to
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CountryTransferRule {
+    pub to_countries: Vec<String>,
+    pub to_groups: Vec<Symbol>,
+}
 
 #[contract]
 pub struct JurisdictionModule;
@@ -22,18 +66,21 @@ impl JurisdictionModule {
 
     pub fn configure_allowed_jurisdictions(env: Env, token: Address, jurisdictions: Vec<String>) {
         Self::require_admin(&env);
+        Self::validate_country_codes(&jurisdictions);
         let key = (ALLOWED_JURISDICTIONS, token);
         env.storage().persistent().set(&key, &jurisdictions);
     }
 
     pub fn configure_denied_jurisdictions(env: Env, token: Address, jurisdictions: Vec<String>) {
         Self::require_admin(&env);
+        Self::validate_country_codes(&jurisdictions);
         let key = (DENIED_JURISDICTIONS, token);
         env.storage().persistent().set(&key, &jurisdictions);
     }
 
     pub fn add_allowed_jurisdiction(env: Env, token: Address, jurisdiction: String) {
         Self::require_admin(&env);
+        Self::validate_country_code(&jurisdiction);
         let key = (ALLOWED_JURISDICTIONS, token.clone());
         let mut jurisdictions: Vec<String> = env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env));
 
@@ -56,6 +103,7 @@ impl JurisdictionModule {
 
     pub fn add_denied_jurisdiction(env: Env, token: Address, jurisdiction: String) {
         Self::require_admin(&env);
+        Self::validate_country_code(&jurisdiction);
         let key = (DENIED_JURISDICTIONS, token.clone());
         let mut jurisdictions: Vec<String> = env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env));
 
@@ -76,23 +124,165 @@ impl JurisdictionModule {
         }
     }
 
+    // Named country groups (EU, EEA, OFAC, FATF grey list...) shared by all
+    // tokens and referenced from allow/deny lists and transfer rules
+    pub fn set_country_group(env: Env, group: Symbol, countries: Vec<String>) {
+        Self::require_admin(&env);
+        Self::validate_country_codes(&countries);
+
+        let key = (COUNTRY_GROUPS, group.clone());
+        env.storage().persistent().set(&key, &countries);
+
+        env.events().publish(
+            (symbol_short!("GROUP_SET"),),
+            (group, countries)
+        );
+    }
+
+    // Refused while any token's allow/deny groups or transfer rules still
+    // reference the group
+    pub fn remove_country_group(env: Env, group: Symbol) {
+        Self::require_admin(&env);
+        if Self::get_group_references(env.clone(), group.clone()) > 0 {
+            panic!("Country group in use");
+        }
+
+        let key = (COUNTRY_GROUPS, group.clone());
+        env.storage().persistent().remove(&key);
+
+        env.events().publish(
+            (symbol_short!("GROUP_REM"),),
+            group
+        );
+    }
+
+    pub fn get_group_references(env: Env, group: Symbol) -> u32 {
+        env.storage().persistent().get(&(GROUP_REFS, group)).unwrap_or(0)
+    }
+
+    pub fn get_country_group(env: Env, group: Symbol) -> Vec<String> {
+        let key = (COUNTRY_GROUPS, group);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn configure_allowed_groups(env: Env, token: Address, groups: Vec<Symbol>) {
+        Self::require_admin(&env);
+        Self::validate_groups(&env, &groups);
+        let key = (ALLOWED_GROUPS, token);
+        let previous: Vec<Symbol> = env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env));
+        Self::update_group_references(&env, &previous, &groups);
+        env.storage().persistent().set(&key, &groups);
+    }
+
+    pub fn configure_denied_groups(env: Env, token: Address, groups: Vec<Symbol>) {
+        Self::require_admin(&env);
+        Self::validate_groups(&env, &groups);
+        let key = (DENIED_GROUPS, token);
+        let previous: Vec<Symbol> = env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env));
+        Self::update_group_references(&env, &previous, &groups);
+        env.storage().persistent().set(&key, &groups);
+    }
+
+    // Directional rule: holders in from_country may only transfer to the
+    // listed countries and groups. Countries without a rule are unrestricted
+    pub fn set_country_transfer_rule(
+        env: Env,
+        token: Address,
+        from_country: String,
+        to_countries: Vec<String>,
+        to_groups: Vec<Symbol>,
+    ) {
+        Self::require_admin(&env);
+        Self::validate_country_code(&from_country);
+        Self::validate_country_codes(&to_countries);
+        Self::validate_groups(&env, &to_groups);
+
+        let key = (TRANSFER_RULES, token, from_country);
+        let previous = env.storage().persistent().get::<_, CountryTransferRule>(&key)
+            .map_or_else(|| Vec::new(&env), |rule| rule.to_groups);
+        Self::update_group_references(&env, &previous, &to_groups);
+
+        let rule = CountryTransferRule { to_countries, to_groups };
+        env.storage().persistent().set(&key, &rule);
+    }
+
+    pub fn remove_country_transfer_rule(env: Env, token: Address, from_country: String) {
+        Self::require_admin(&env);
+        let key = (TRANSFER_RULES, token, from_country);
+        if let Some(rule) = env.storage().persistent().get::<_, CountryTransferRule>(&key) {
+            Self::update_group_references(&env, &rule.to_groups, &Vec::new(&env));
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    pub fn get_country_transfer_rule(env: Env, token: Address, from_country: String) -> Option<CountryTransferRule> {
+        let key = (TRANSFER_RULES, token, from_country);
+        env.storage().persistent().get(&key)
+    }
+
+    pub fn is_country_pair_allowed(env: Env, token: Address, from_country: String, to_country: String) -> bool {
+        match Self::get_country_transfer_rule(env.clone(), token, from_country) {
+            Some(rule) => {
+                rule.to_countries.contains(&to_country)
+                    || Self::in_any_group(&env, &rule.to_groups, &to_country)
+            }
+            None => true,
+        }
+    }
+
+    // Investor countries are looked up in the token's IdentityRegistry
+    pub fn set_jurisdiction_registry(env: Env, token: Address, identity_registry: Address) {
+        Self::require_admin(&env);
+        let key = (IDENTITY_REGISTRY, token);
+        env.storage().persistent().set(&key, &identity_registry);
+    }
+
+    // Whether wallets without a known country may transfer; denied by default.
+    // Without an identity registry every country is unknown, so an enabled
+    // token cannot be switched back to deny until a registry is set
+    pub fn set_unknown_jurisdiction_policy(env: Env, token: Address, allow: bool) {
+        Self::require_admin(&env);
+        let key = (ALLOW_UNKNOWN, token.clone());
+        env.storage().persistent().set(&key, &allow);
+
+        if Self::is_enabled_for_token(env.clone(), token.clone()) {
+            Self::require_registry_or_unknown_allowed(&env, &token);
+        }
+    }
+
+    pub fn allows_unknown_jurisdiction(env: Env, token: Address) -> bool {
+        let key = (ALLOW_UNKNOWN, token);
+        env.storage().persistent().get(&key).unwrap_or(false)
+    }
+
+    pub fn get_investor_jurisdiction(env: Env, token: Address, wallet: Address) -> Option<String> {
+        Self::get_address_jurisdiction(&env, &token, &wallet)
+    }
+
     pub fn is_jurisdiction_allowed(env: Env, token: Address, jurisdiction: String) -> bool {
         let denied_key = (DENIED_JURISDICTIONS, token.clone());
         let denied: Vec<String> = env.storage().persistent().get(&denied_key).unwrap_or_else(|| Vec::new(&env));
+        let denied_groups: Vec<Symbol> = env.storage().persistent().get(&(DENIED_GROUPS, token.clone())).unwrap_or_else(|| Vec::new(&env));
 
-        if denied.contains(&jurisdiction) {
+        if denied.contains(&jurisdiction) || Self::in_any_group(&env, &denied_groups, &jurisdiction) {
             return false;
         }
 
-        let allowed_key = (ALLOWED_JURISDICTIONS, token);
+        let allowed_key = (ALLOWED_JURISDICTIONS, token.clone());
         let allowed: Vec<String> = env.storage().persistent().get(&allowed_key).unwrap_or_else(|| Vec::new(&env));
+        let allowed_groups: Vec<Symbol> = env.storage().persistent().get(&(ALLOWED_GROUPS, token)).unwrap_or_else(|| Vec::new(&env));
 
         // If no allowed list is set, allow all (except denied)
-        allowed.is_empty() || allowed.contains(&jurisdiction)
+        (allowed.is_empty() && allowed_groups.is_empty())
+            || allowed.contains(&jurisdiction)
+            || Self::in_any_group(&env, &allowed_groups, &jurisdiction)
     }
 
+    // Refused while the token has no identity registry and unknown countries
+    // are denied, which would block every transfer
     pub fn enable_for_token(env: Env, token: Address) {
         Self::require_admin(&env);
+        Self::require_registry_or_unknown_allowed(&env, &token);
         let key = (ENABLED_TOKENS, token.clone());
         env.storage().persistent().set(&key, &true);
     }
@@ -114,17 +304,90 @@ impl JurisdictionModule {
         }
 
         // Check jurisdiction for both from and to addresses
-        let from_jurisdiction = Self::get_address_jurisdiction(&env, &context.from);
-        let to_jurisdiction = Self::get_address_jurisdiction(&env, &context.to);
+        let from_jurisdiction = Self::get_address_jurisdiction(&env, &context.token, &context.from);
+        let to_jurisdiction = Self::get_address_jurisdiction(&env, &context.token, &context.to);
+
+        if !Self::is_party_allowed(&env, &context.token, &from_jurisdiction)
+            || !Self::is_party_allowed(&env, &context.token, &to_jurisdiction)
+        {
+            return false;
+        }
+
+        match (from_jurisdiction, to_jurisdiction) {
+            (Some(from), Some(to)) => Self::is_country_pair_allowed(env, context.token, from, to),
+            _ => true,
+        }
+    }
 
-        Self::is_jurisdiction_allowed(env.clone(), context.token.clone(), from_jurisdiction) &&
-        Self::is_jurisdiction_allowed(env.clone(), context.token.clone(), to_jurisdiction)
+    fn is_party_allowed(env: &Env, token: &Address, jurisdiction: &Option<String>) -> bool {
+        match jurisdiction {
+            Some(jurisdiction) => Self::is_jurisdiction_allowed(env.clone(), token.clone(), jurisdiction.clone()),
+            None => Self::allows_unknown_jurisdiction(env.clone(), token.clone()),
+        }
+    }
+
+    fn require_registry_or_unknown_allowed(env: &Env, token: &Address) {
+        if !env.storage().persistent().has(&(IDENTITY_REGISTRY, token.clone()))
+            && !Self::allows_unknown_jurisdiction(env.clone(), token.clone())
+        {
+            panic!("Identity registry not set");
+        }
+    }
+
+    fn in_any_group(env: &Env, groups: &Vec<Symbol>, country: &String) -> bool {
+        groups.iter().any(|group| Self::get_country_group(env.clone(), group).contains(country))
+    }
+
+    // Moves group reference counts from a replaced group list to its replacement
+    fn update_group_references(env: &Env, previous: &Vec<Symbol>, groups: &Vec<Symbol>) {
+        for group in previous.iter() {
+            let key = (GROUP_REFS, group);
+            let references: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+            if references > 1 {
+                env.storage().persistent().set(&key, &(references - 1));
+            } else {
+                env.storage().persistent().remove(&key);
+            }
+        }
+        for group in groups.iter() {
+            let key = (GROUP_REFS, group);
+            let references: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+            env.storage().persistent().set(&key, &(references + 1));
+        }
+    }
+
+    fn validate_groups(env: &Env, groups: &Vec<Symbol>) {
+        for group in groups.iter() {
+            if !env.storage().persistent().has(&(COUNTRY_GROUPS, group)) {
+                panic!("Unknown country group");
+            }
+        }
+    }
+
+    fn validate_country_codes(countries: &Vec<String>) {
+        for country in countries.iter() {
+            Self::validate_country_code(&country);
+        }
+    }
+
+    fn validate_country_code(country: &String) {
+        if country.len() == 2 {
+            let mut code = [0u8; 2];
+            country.copy_into_slice(&mut code);
+            if ISO_3166_ALPHA2.iter().any(|assigned| assigned.as_bytes() == code) {
+                return;
+            }
+        }
+        panic!("Invalid ISO 3166 alpha-2 country code");
     }
 
-    fn get_address_jurisdiction(env: &Env, address: &Address) -> String {
-        // This would query the IdentityRegistry for jurisdiction claim
-        // For now, return a default jurisdiction
-        String::from_str(env, "US")
+    fn get_address_jurisdiction(env: &Env, token: &Address, address: &Address) -> Option<String> {
+        let identity_registry: Address = env.storage().persistent().get(&(IDENTITY_REGISTRY, token.clone()))?;
+        env.invoke_contract(
+            &identity_registry,
+            &Symbol::new(env, "get_investor_country"),
+            vec![env, address.into_val(env)],
+        )
     }
 
     fn require_admin(env: &Env) {
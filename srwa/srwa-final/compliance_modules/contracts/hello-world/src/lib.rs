@@ -4,9 +4,16 @@ pub mod jurisdiction_module;
 pub mod pause_freeze_module;
 pub mod max_holders_module;
 pub mod lockup_module;
+pub mod holding_period_module;
+pub mod transfer_limits_module;
+pub mod concentration_module;
+pub mod investment_limits_module;
 
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, vec, Address, Bytes, Env, IntoVal, Symbol};
 
+// Mirrors ComplianceCore's TransferContext. from_balance, to_balance and
+// total_supply are the values after the transfer (for checks, the values it
+// would leave); modules must use them rather than calling back into the token
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct TransferContext {
@@ -14,6 +21,11 @@ pub struct TransferContext {
     pub to: Address,
     pub amount: i128,
     pub token: Address,
+    pub partition: Symbol,
+    pub memo: Option<Bytes>,
+    pub from_balance: i128,
+    pub to_balance: i128,
+    pub total_supply: i128,
 }
 
 pub trait ComplianceModule {
@@ -22,6 +34,24 @@ pub trait ComplianceModule {
     fn disable(env: soroban_sdk::Env, token: Address);
     fn check(env: soroban_sdk::Env, context: &TransferContext) -> bool;
     fn transferred(env: soroban_sdk::Env, context: &TransferContext);
-    fn created(env: soroban_sdk::Env, to: Address, amount: i128, token: Address);
-    fn destroyed(env: soroban_sdk::Env, from: Address, amount: i128, token: Address);
-}
\ No newline at end of file
+    // `balance` and `total_supply` are the holder's balance and the token's
+    // supply after the mint or burn
+    fn created(env: soroban_sdk::Env, to: Address, amount: i128, token: Address, balance: i128, total_supply: i128);
+    fn destroyed(env: soroban_sdk::Env, from: Address, amount: i128, token: Address, balance: i128, total_supply: i128);
+}
+
+// Resolves a wallet to the investor identity it belongs to (the identity's
+// primary wallet) through the token's IdentityRegistry. Without a registry
+// every wallet is its own investor.
+pub(crate) fn resolve_investor(env: &Env, identity_registry: &Option<Address>, wallet: &Address) -> Address {
+    match identity_registry {
+        Some(registry) => env.invoke_contract(
+            registry,
+            &Symbol::new(env, "resolve_investor"),
+            vec![env, wallet.into_val(env)],
+        ),
+        None => wallet.clone(),
+    }
+}
+
+mod test;
@@ -1,15 +1,18 @@
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Env, IntoVal, Symbol, Vec
 };
 use crate::TransferContext;
 
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const LOCKUPS: Symbol = symbol_short!("LOCKUPS");
 const VESTING_SCHEDULES: Symbol = symbol_short!("VESTING");
+const NEXT_LOCKUP_ID: Symbol = symbol_short!("LOCK_NEXT");
+const TREASURY: Symbol = symbol_short!("TREASURY");
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct LockupSchedule {
+    pub lockup_id: u32,
     pub holder: Address,
     pub total_amount: i128,
     pub released_amount: i128,
@@ -40,6 +43,8 @@ impl LockupModule {
         env.storage().instance().set(&ADMIN, &admin);
     }
 
+    // A holder can have several overlapping lockups; each gets its own id
+    #[allow(clippy::too_many_arguments)]
     pub fn create_lockup(
         env: Env,
         token: Address,
@@ -49,10 +54,22 @@ impl LockupModule {
         cliff_time: u64,
         end_time: u64,
         revocable: bool,
-    ) {
+    ) -> u32 {
         Self::require_admin(&env);
 
+        if amount <= 0 {
+            panic!("Lockup amount must be positive");
+        }
+        if cliff_time < start_time || end_time < cliff_time {
+            panic!("Invalid lockup schedule");
+        }
+
+        let id_key = (NEXT_LOCKUP_ID, token.clone(), holder.clone());
+        let lockup_id: u32 = env.storage().persistent().get(&id_key).unwrap_or(0);
+        env.storage().persistent().set(&id_key, &(lockup_id + 1));
+
         let lockup = LockupSchedule {
+            lockup_id,
             holder: holder.clone(),
             total_amount: amount,
             released_amount: 0,
@@ -64,12 +81,25 @@ impl LockupModule {
         };
 
         let key = (LOCKUPS, token.clone(), holder.clone());
-        env.storage().persistent().set(&key, &lockup);
+        let mut lockups = Self::get_lockups(env.clone(), token.clone(), holder.clone());
+        lockups.push_back(lockup);
+        env.storage().persistent().set(&key, &lockups);
 
         env.events().publish(
             (symbol_short!("LOCK_CRE"),),
-            (token, holder, amount, start_time, cliff_time, end_time)
+            (token, holder, lockup_id, amount, start_time, cliff_time, end_time)
         );
+
+        lockup_id
+    }
+
+    pub fn get_lockups(env: Env, token: Address, holder: Address) -> Vec<LockupSchedule> {
+        let key = (LOCKUPS, token, holder);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn get_lockup(env: Env, token: Address, holder: Address, lockup_id: u32) -> Option<LockupSchedule> {
+        Self::get_lockups(env, token, holder).iter().find(|lockup| lockup.lockup_id == lockup_id)
     }
 
     pub fn create_vesting_schedule(
@@ -89,39 +119,90 @@ impl LockupModule {
         );
     }
 
-    pub fn revoke_lockup(env: Env, token: Address, holder: Address) {
+    // Unvested tokens of revoked lockups are returned here
+    pub fn set_lockup_treasury(env: Env, token: Address, treasury: Address) {
+        Self::require_admin(&env);
+        let key = (TREASURY, token);
+        env.storage().persistent().set(&key, &treasury);
+    }
+
+    pub fn get_lockup_treasury(env: Env, token: Address) -> Option<Address> {
+        let key = (TREASURY, token);
+        env.storage().persistent().get(&key)
+    }
+
+    // Vested tokens stay with the holder; the unvested remainder is moved to
+    // the treasury with the token's force_transfer, so the token admin must
+    // authorize this call as well. The token reports that transfer to its
+    // compliance contract, so register this module there with only the check
+    // hook (check_lockup, transferred None) or the report re-enters it.
+    pub fn revoke_lockup(env: Env, token: Address, holder: Address, lockup_id: u32) {
         Self::require_admin(&env);
 
         let key = (LOCKUPS, token.clone(), holder.clone());
-        if let Some(mut lockup) = env.storage().persistent().get::<_, LockupSchedule>(&key) {
-            if lockup.revocable && !lockup.revoked {
-                lockup.revoked = true;
-                env.storage().persistent().set(&key, &lockup);
-
-                env.events().publish(
-                    (symbol_short!("LOCK_REV"),),
-                    (token, holder)
-                );
-            }
+        let mut lockups = Self::get_lockups(env.clone(), token.clone(), holder.clone());
+        let index = lockups.iter()
+            .position(|lockup| lockup.lockup_id == lockup_id)
+            .unwrap_or_else(|| panic!("Lockup not found")) as u32;
+
+        let mut lockup = lockups.get(index).unwrap();
+        if !lockup.revocable {
+            panic!("Lockup not revocable");
         }
+        if lockup.revoked {
+            panic!("Lockup already revoked");
+        }
+
+        let releasable = Self::calculate_releasable_amount(&lockup, env.ledger().timestamp());
+        let unvested = lockup.total_amount - lockup.released_amount - releasable;
+        let vested = lockup.total_amount - unvested;
+
+        let treasury = Self::get_lockup_treasury(env.clone(), token.clone());
+        if unvested > 0 && treasury.is_none() {
+            panic!("Lockup treasury not set");
+        }
+
+        lockup.revoked = true;
+        lockups.set(index, lockup);
+        env.storage().persistent().set(&key, &lockups);
+
+        if unvested > 0 {
+            env.invoke_contract::<()>(
+                &token,
+                &Symbol::new(&env, "force_transfer"),
+                vec![&env, holder.into_val(&env), treasury.unwrap().into_val(&env), unvested.into_val(&env)],
+            );
+        }
+
+        env.events().publish(
+            (symbol_short!("LOCK_REV"),),
+            (token, holder, lockup_id, vested, unvested)
+        );
     }
 
     pub fn release_vested_tokens(env: Env, token: Address, holder: Address) -> i128 {
         let current_time = env.ledger().timestamp();
         let mut total_released = 0i128;
 
-        // Check linear vesting lockup
+        // Check linear vesting lockups
         let lockup_key = (LOCKUPS, token.clone(), holder.clone());
-        if let Some(mut lockup) = env.storage().persistent().get::<_, LockupSchedule>(&lockup_key) {
+        let mut lockups = Self::get_lockups(env.clone(), token.clone(), holder.clone());
+        let mut lockups_updated = false;
+        for i in 0..lockups.len() {
+            let mut lockup = lockups.get(i).unwrap();
             if !lockup.revoked {
                 let releasable = Self::calculate_releasable_amount(&lockup, current_time);
                 if releasable > 0 {
                     lockup.released_amount += releasable;
-                    env.storage().persistent().set(&lockup_key, &lockup);
+                    lockups.set(i, lockup);
                     total_released += releasable;
+                    lockups_updated = true;
                 }
             }
         }
+        if lockups_updated {
+            env.storage().persistent().set(&lockup_key, &lockups);
+        }
 
         // Check milestone vesting schedule
         let vesting_key = (VESTING_SCHEDULES, token.clone(), holder.clone());
@@ -155,9 +236,8 @@ impl LockupModule {
         let current_time = env.ledger().timestamp();
         let mut locked_amount = 0i128;
 
-        // Check linear vesting lockup
-        let lockup_key = (LOCKUPS, token.clone(), holder.clone());
-        if let Some(lockup) = env.storage().persistent().get::<_, LockupSchedule>(&lockup_key) {
+        // Check linear vesting lockups
+        for lockup in Self::get_lockups(env.clone(), token.clone(), holder.clone()).iter() {
             if !lockup.revoked {
                 let releasable = Self::calculate_releasable_amount(&lockup, current_time);
                 locked_amount += lockup.total_amount - lockup.released_amount - releasable;
@@ -178,14 +258,10 @@ impl LockupModule {
         locked_amount
     }
 
+    // The sender must keep at least the locked amount after the transfer
     pub fn check_lockup(env: Env, context: TransferContext) -> bool {
         let locked_amount = Self::get_locked_amount(env.clone(), context.token.clone(), context.from.clone());
-
-        // This would need integration with the token contract to check actual balance
-        // For now, simplified check assuming transfer amount doesn't exceed unlocked tokens
-        // In reality, we'd need: balance - locked_amount >= transfer_amount
-
-        true // Simplified for now
+        locked_amount <= 0 || context.from_balance >= locked_amount
     }
 
     fn calculate_releasable_amount(lockup: &LockupSchedule, current_time: u64) -> i128 {
@@ -1,12 +1,40 @@
 use soroban_sdk::{
-    contract, contractimpl, symbol_short, Address, Env, Symbol
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Env, IntoVal, String, Symbol, Vec
 };
-use crate::TransferContext;
+use crate::{resolve_investor, TransferContext};
 
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const MAX_HOLDERS: Symbol = symbol_short!("MAX_HOLD");
 const HOLDER_COUNT: Symbol = symbol_short!("HOLD_CNT");
 const HOLDERS: Symbol = symbol_short!("HOLDERS");
+const IDENTITY_REGISTRY: Symbol = symbol_short!("ID_REG");
+const COMPLIANCE: Symbol = symbol_short!("COMPLY");
+const CATEGORIES: Symbol = symbol_short!("HOLD_CAT");
+const CATEGORY_CAP: Symbol = symbol_short!("CAT_CAP");
+const CATEGORY_COUNT: Symbol = symbol_short!("CAT_CNT");
+const COUNTRY_CAP: Symbol = symbol_short!("CTRY_CAP");
+const COUNTRY_COUNT: Symbol = symbol_short!("CTRY_CNT");
+const HOLDER_INFO: Symbol = symbol_short!("HOLD_INFO");
+const FUNDED: Symbol = symbol_short!("HOLD_FUND");
+const FUNDED_WALLETS: Symbol = symbol_short!("HOLD_WLTS");
+
+// An investor falls in the first category whose claim topic they are
+// verified for, e.g. accredited (topic 3) before professional
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct HolderCategory {
+    pub category: Symbol,
+    pub topic_id: u32,
+}
+
+// Category and country an investor was counted under when they became a
+// holder; country is empty when unknown
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct HolderInfo {
+    pub category: Symbol,
+    pub country: String,
+}
 
 #[contract]
 pub struct MaxHoldersModule;
@@ -31,6 +59,66 @@ impl MaxHoldersModule {
         );
     }
 
+    // Holders are counted per investor identity when a registry is set, so
+    // several wallets of one investor count once
+    pub fn set_max_holders_registry(env: Env, token: Address, identity_registry: Address) {
+        Self::require_admin(&env);
+        let key = (IDENTITY_REGISTRY, token);
+        env.storage().persistent().set(&key, &identity_registry);
+    }
+
+    // Holder bookkeeping and the hooks only accept calls from this contract
+    pub fn set_max_holders_compliance(env: Env, compliance: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&COMPLIANCE, &compliance);
+    }
+
+    // Investors matching none of the categories fall in `default_category`
+    pub fn set_holder_categories(env: Env, token: Address, categories: Vec<HolderCategory>, default_category: Symbol) {
+        Self::require_admin(&env);
+        let key = (CATEGORIES, token);
+        env.storage().persistent().set(&key, &(categories, default_category));
+    }
+
+    // 0 removes the cap
+    pub fn set_holder_category_cap(env: Env, token: Address, category: Symbol, cap: u32) {
+        Self::require_admin(&env);
+        let key = (CATEGORY_CAP, token.clone(), category.clone());
+        env.storage().persistent().set(&key, &cap);
+
+        env.events().publish(
+            (symbol_short!("CAT_CAP"),),
+            (token, category, cap)
+        );
+    }
+
+    pub fn set_holder_country_cap(env: Env, token: Address, country: String, cap: u32) {
+        Self::require_admin(&env);
+        let key = (COUNTRY_CAP, token.clone(), country.clone());
+        env.storage().persistent().set(&key, &cap);
+
+        env.events().publish(
+            (symbol_short!("CTRY_CAP"),),
+            (token, country, cap)
+        );
+    }
+
+    pub fn get_holder_category_count(env: Env, token: Address, category: Symbol) -> u32 {
+        let key = (CATEGORY_COUNT, token, category);
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    pub fn get_holder_country_count(env: Env, token: Address, country: String) -> u32 {
+        let key = (COUNTRY_COUNT, token, country);
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    // Category and country the wallet's investor would be counted under now
+    pub fn get_holder_info(env: Env, token: Address, wallet: Address) -> HolderInfo {
+        let investor = Self::investor_of(&env, &token, &wallet);
+        Self::classify(&env, &token, &investor)
+    }
+
     pub fn get_max_holders(env: Env, token: Address) -> u32 {
         let key = (MAX_HOLDERS, token);
         env.storage().persistent().get(&key).unwrap_or(0)
@@ -47,6 +135,141 @@ impl MaxHoldersModule {
     }
 
     pub fn add_holder(env: Env, token: Address, address: Address) {
+        Self::require_compliance(&env);
+        Self::add_holder_internal(&env, &token, &address);
+    }
+
+    pub fn remove_holder(env: Env, token: Address, address: Address) {
+        Self::require_compliance(&env);
+        Self::remove_holder_internal(&env, &token, &address);
+    }
+
+    pub fn check_max_holders(env: Env, context: TransferContext) -> bool {
+        let to_investor = Self::investor_of(&env, &context.token, &context.to);
+
+        // Moving between wallets of the same investor never adds a holder
+        if Self::investor_of(&env, &context.token, &context.from) == to_investor {
+            return true;
+        }
+
+        // If recipient is already a holder, allow transfer
+        if Self::is_holder(env.clone(), context.token.clone(), to_investor.clone()) {
+            return true;
+        }
+
+        // Check if adding a new holder would exceed the limit
+        let max_holders = Self::get_max_holders(env.clone(), context.token.clone());
+        if max_holders > 0 && Self::get_holder_count(env.clone(), context.token.clone()) >= max_holders {
+            return false;
+        }
+
+        // And the caps of the recipient's category and country
+        let info = Self::classify(&env, &context.token, &to_investor);
+
+        let category_cap: u32 = env.storage().persistent()
+            .get(&(CATEGORY_CAP, context.token.clone(), info.category.clone()))
+            .unwrap_or(0);
+        if category_cap > 0
+            && Self::get_holder_category_count(env.clone(), context.token.clone(), info.category) >= category_cap
+        {
+            return false;
+        }
+
+        if !info.country.is_empty() {
+            let country_cap: u32 = env.storage().persistent()
+                .get(&(COUNTRY_CAP, context.token.clone(), info.country.clone()))
+                .unwrap_or(0);
+            if country_cap > 0
+                && Self::get_holder_country_count(env.clone(), context.token.clone(), info.country) >= country_cap
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn transferred(env: Env, context: TransferContext) {
+        Self::require_compliance(&env);
+
+        if context.amount <= 0 {
+            return;
+        }
+
+        // Recipient first, so moving between two wallets of one investor
+        // never drops them as a holder in between
+        Self::update_wallet(&env, &context.token, &context.to, context.to_balance);
+        if context.from != context.to {
+            Self::update_wallet(&env, &context.token, &context.from, context.from_balance);
+        }
+    }
+
+    pub fn created(env: Env, to: Address, amount: i128, token: Address, balance: i128, _total_supply: i128) {
+        Self::require_compliance(&env);
+
+        // Called when tokens are minted
+        if amount > 0 {
+            Self::update_wallet(&env, &token, &to, balance);
+        }
+    }
+
+    // Called after burns and clawbacks
+    pub fn destroyed(env: Env, from: Address, _amount: i128, token: Address, balance: i128, _total_supply: i128) {
+        Self::require_compliance(&env);
+        Self::update_wallet(&env, &token, &from, balance);
+    }
+
+    // Tracks how many of an investor's wallets hold a balance, from the
+    // post-operation balances the compliance contract passes in. An investor
+    // stops being a holder once none of their wallets does; wallets funded
+    // before the module tracked the token are not counted. Each funded wallet
+    // remembers the investor it was counted under, so a wallet linked to
+    // another identity since moves its count over
+    fn update_wallet(env: &Env, token: &Address, wallet: &Address, balance: i128) {
+        let investor = Self::investor_of(env, token, wallet);
+        let funded_key = (FUNDED, token.clone(), wallet.clone());
+        let mut was_funded = false;
+
+        if let Some(counted_under) = env.storage().persistent().get::<_, Address>(&funded_key) {
+            if counted_under == investor {
+                was_funded = true;
+            } else {
+                env.storage().persistent().remove(&funded_key);
+                Self::release_wallet(env, token, &counted_under);
+            }
+        }
+
+        if balance > 0 {
+            if !was_funded {
+                let count_key = (FUNDED_WALLETS, token.clone(), investor.clone());
+                let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+                env.storage().persistent().set(&funded_key, &investor);
+                env.storage().persistent().set(&count_key, &(count + 1));
+            }
+            Self::add_holder_internal(env, token, &investor);
+        } else if was_funded {
+            env.storage().persistent().remove(&funded_key);
+            Self::release_wallet(env, token, &investor);
+        } else if !env.storage().persistent().has(&(FUNDED_WALLETS, token.clone(), investor.clone())) {
+            Self::remove_holder_internal(env, token, &investor);
+        }
+    }
+
+    // One of the investor's funded wallets emptied or moved to another
+    // identity; the investor stops being a holder with their last one
+    fn release_wallet(env: &Env, token: &Address, investor: &Address) {
+        let count_key = (FUNDED_WALLETS, token.clone(), investor.clone());
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+
+        if count > 1 {
+            env.storage().persistent().set(&count_key, &(count - 1));
+        } else {
+            env.storage().persistent().remove(&count_key);
+            Self::remove_holder_internal(env, token, investor);
+        }
+    }
+
+    fn add_holder_internal(env: &Env, token: &Address, address: &Address) {
         if !Self::is_holder(env.clone(), token.clone(), address.clone()) {
             let holder_key = (HOLDERS, token.clone(), address.clone());
             env.storage().persistent().set(&holder_key, &true);
@@ -55,14 +278,18 @@ impl MaxHoldersModule {
             let current_count = Self::get_holder_count(env.clone(), token.clone());
             env.storage().persistent().set(&count_key, &(current_count + 1));
 
+            let info = Self::classify(env, token, address);
+            Self::adjust_breakdown(env, token, &info, true);
+            env.storage().persistent().set(&(HOLDER_INFO, token.clone(), address.clone()), &info);
+
             env.events().publish(
                 (symbol_short!("HOLD_ADD"),),
-                (token, address, current_count + 1)
+                (token.clone(), address.clone(), current_count + 1)
             );
         }
     }
 
-    pub fn remove_holder(env: Env, token: Address, address: Address) {
+    fn remove_holder_internal(env: &Env, token: &Address, address: &Address) {
         if Self::is_holder(env.clone(), token.clone(), address.clone()) {
             let holder_key = (HOLDERS, token.clone(), address.clone());
             env.storage().persistent().remove(&holder_key);
@@ -73,60 +300,82 @@ impl MaxHoldersModule {
                 env.storage().persistent().set(&count_key, &(current_count - 1));
             }
 
+            // Decrement what the holder was counted under, even if their
+            // claims or country changed since
+            let info_key = (HOLDER_INFO, token.clone(), address.clone());
+            if let Some(info) = env.storage().persistent().get::<_, HolderInfo>(&info_key) {
+                Self::adjust_breakdown(env, token, &info, false);
+                env.storage().persistent().remove(&info_key);
+            }
+
             env.events().publish(
                 (symbol_short!("HOLD_REM"),),
-                (token, address, current_count.saturating_sub(1))
+                (token.clone(), address.clone(), current_count.saturating_sub(1))
             );
         }
     }
 
-    pub fn check_max_holders(env: Env, context: TransferContext) -> bool {
-        let max_holders = Self::get_max_holders(env.clone(), context.token.clone());
+    fn classify(env: &Env, token: &Address, investor: &Address) -> HolderInfo {
+        let identity_registry: Option<Address> = env.storage().persistent().get(&(IDENTITY_REGISTRY, token.clone()));
+        let categories: Option<(Vec<HolderCategory>, Symbol)> = env.storage().persistent().get(&(CATEGORIES, token.clone()));
 
-        // If no limit is set, allow transfer
-        if max_holders == 0 {
-            return true;
+        let mut category = symbol_short!("DEFAULT");
+        let mut country = String::from_str(env, "");
+
+        if let Some((categories, default_category)) = categories {
+            category = default_category;
+            if let Some(registry) = &identity_registry {
+                for candidate in categories.iter() {
+                    let verified: bool = env.invoke_contract(
+                        registry,
+                        &Symbol::new(env, "is_verified_for_topics"),
+                        vec![env, investor.into_val(env), vec![env, candidate.topic_id].into_val(env)],
+                    );
+                    if verified {
+                        category = candidate.category;
+                        break;
+                    }
+                }
+            }
         }
 
-        // If recipient is already a holder, allow transfer
-        if Self::is_holder(env.clone(), context.token.clone(), context.to.clone()) {
-            return true;
+        if let Some(registry) = &identity_registry {
+            let investor_country: Option<String> = env.invoke_contract(
+                registry,
+                &Symbol::new(env, "get_investor_country"),
+                vec![env, investor.into_val(env)],
+            );
+            if let Some(investor_country) = investor_country {
+                country = investor_country;
+            }
         }
 
-        // Check if adding a new holder would exceed the limit
-        let current_count = Self::get_holder_count(env.clone(), context.token.clone());
-        current_count < max_holders
+        HolderInfo { category, country }
     }
 
-    pub fn transferred(env: Env, context: TransferContext) {
-        // This would be called after a successful transfer
-        // to update holder status
+    fn adjust_breakdown(env: &Env, token: &Address, info: &HolderInfo, added: bool) {
+        let category_key = (CATEGORY_COUNT, token.clone(), info.category.clone());
+        let count: u32 = env.storage().persistent().get(&category_key).unwrap_or(0);
+        let count = if added { count + 1 } else { count.saturating_sub(1) };
+        env.storage().persistent().set(&category_key, &count);
 
-        // Check if recipient should be added as a holder
-        // (This would need integration with token contract to check balance)
-        if context.amount > 0 && !Self::is_holder(env.clone(), context.token.clone(), context.to.clone()) {
-            Self::add_holder(env.clone(), context.token.clone(), context.to.clone());
+        if !info.country.is_empty() {
+            let country_key = (COUNTRY_COUNT, token.clone(), info.country.clone());
+            let count: u32 = env.storage().persistent().get(&country_key).unwrap_or(0);
+            let count = if added { count + 1 } else { count.saturating_sub(1) };
+            env.storage().persistent().set(&country_key, &count);
         }
-
-        // Check if sender should be removed as a holder
-        // (This would need integration with token contract to check if balance is now 0)
-        // For now, we don't automatically remove holders as we can't check balances
     }
 
-    pub fn created(env: Env, to: Address, amount: i128, token: Address) {
-        // Called when tokens are minted
-        if amount > 0 && !Self::is_holder(env.clone(), token.clone(), to.clone()) {
-            Self::add_holder(env.clone(), token.clone(), to.clone());
-        }
+    fn require_compliance(env: &Env) {
+        let compliance: Address = env.storage().instance().get(&COMPLIANCE)
+            .unwrap_or_else(|| panic!("Compliance contract not set"));
+        compliance.require_auth();
     }
 
-    pub fn destroyed(env: Env, from: Address, amount: i128, token: Address) {
-        // Called when tokens are burned
-        // Would need to check if balance is now 0 to remove holder
-        // For now, simplified implementation
-        if amount > 0 {
-            // Don't automatically remove - would need balance check
-        }
+    fn investor_of(env: &Env, token: &Address, wallet: &Address) -> Address {
+        let identity_registry: Option<Address> = env.storage().persistent().get(&(IDENTITY_REGISTRY, token.clone()));
+        resolve_investor(env, &identity_registry, wallet)
     }
 
     fn require_admin(env: &Env) {
@@ -1,21 +1,929 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{vec, Env, String};
+use crate::concentration_module::{ConcentrationModule, ConcentrationModuleClient};
+use crate::holding_period_module::{HoldingPeriodModule, HoldingPeriodModuleClient};
+use crate::jurisdiction_module::{JurisdictionModule, JurisdictionModuleClient};
+use crate::investment_limits_module::{
+    InflowRecord, InvestmentLimitsModule, InvestmentLimitsModuleClient, InvestmentTier,
+};
+use crate::lockup_module::{LockupModule, LockupModuleClient};
+use crate::max_holders_module::{HolderCategory, MaxHoldersModule, MaxHoldersModuleClient};
+use crate::transfer_limits_module::{TransferLimitsModule, TransferLimitsModuleClient, VolumeLimit, WindowUnit};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+    vec, Address, Env, IntoVal, String, Symbol, Vec,
+};
+
+// Country and wallet lookups standing in for the token's IdentityRegistry
+#[contract]
+pub struct MockIdentityRegistry;
+
+#[contractimpl]
+impl MockIdentityRegistry {
+    pub fn set_country(env: Env, wallet: Address, country: String) {
+        env.storage().instance().set(&wallet, &country);
+    }
+
+    pub fn get_investor_country(env: Env, wallet: Address) -> Option<String> {
+        env.storage().instance().get(&wallet)
+    }
+
+    pub fn link_wallet(env: Env, wallet: Address, investor: Address) {
+        env.storage().instance().set(&(symbol_short!("LINK"), wallet), &investor);
+    }
+
+    pub fn resolve_investor(env: Env, wallet: Address) -> Address {
+        env.storage().instance().get(&(symbol_short!("LINK"), wallet.clone())).unwrap_or(wallet)
+    }
+
+    pub fn set_topic(env: Env, wallet: Address, topic: u32, verified: bool) {
+        env.storage().instance().set(&(symbol_short!("TOPIC"), wallet, topic), &verified);
+    }
+
+    pub fn is_verified_for_topics(env: Env, wallet: Address, topics: Vec<u32>) -> bool {
+        topics.iter().all(|topic| {
+            env.storage().instance().get(&(symbol_short!("TOPIC"), wallet.clone(), topic)).unwrap_or(false)
+        })
+    }
+}
+
+// Balances and the admin-only force_transfer of SrwaToken
+#[contract]
+pub struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    pub fn init_token(env: Env, admin: Address) {
+        env.storage().instance().set(&symbol_short!("ADMIN"), &admin);
+    }
+
+    pub fn set_balance(env: Env, id: Address, amount: i128) {
+        env.storage().instance().set(&id, &amount);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&id).unwrap_or(0)
+    }
+
+    pub fn force_transfer(env: Env, from: Address, to: Address, amount: i128) {
+        let admin: Address = env.storage().instance().get(&symbol_short!("ADMIN")).unwrap();
+        admin.require_auth();
+        Self::set_balance(env.clone(), from.clone(), Self::balance(env.clone(), from) - amount);
+        Self::set_balance(env.clone(), to.clone(), Self::balance(env, to) + amount);
+    }
+}
+
+fn transfer_context(from: &Address, to: &Address, amount: i128, token: &Address) -> TransferContext {
+    TransferContext {
+        from: from.clone(),
+        to: to.clone(),
+        amount,
+        token: token.clone(),
+        partition: symbol_short!("DEFAULT"),
+        memo: None,
+        from_balance: 0,
+        to_balance: amount,
+        total_supply: amount,
+    }
+}
 
 #[test]
-fn test() {
+fn test_jurisdiction_module() {
     let env = Env::default();
-    let contract_id = env.register(Contract, ());
-    let client = ContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token = Address::generate(&env);
+    let german = Address::generate(&env);
+    let north_korean = Address::generate(&env);
+    let unknown = Address::generate(&env);
+
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.set_country(&german, &String::from_str(&env, "DE"));
+    registry.set_country(&north_korean, &String::from_str(&env, "KP"));
+
+    let contract_id = env.register(JurisdictionModule, ());
+    let client = JurisdictionModuleClient::new(&env, &contract_id);
+    client.init_jurisdiction(&admin);
 
-    let words = client.hello(&String::from_str(&env, "Dev"));
+    // Denying unknown countries without a registry would block every transfer
+    assert!(client.try_enable_for_token(&token).is_err());
+    client.set_jurisdiction_registry(&token, &registry_id);
+    client.enable_for_token(&token);
+
+    client.configure_denied_jurisdictions(&token, &vec![&env, String::from_str(&env, "KP")]);
+    assert_eq!(client.get_investor_jurisdiction(&token, &german), Some(String::from_str(&env, "DE")));
+    assert!(client.check_jurisdiction(&transfer_context(&german, &german, 100, &token)));
+    assert!(!client.check_jurisdiction(&transfer_context(&german, &north_korean, 100, &token)));
+
+    assert!(!client.check_jurisdiction(&transfer_context(&german, &unknown, 100, &token)));
+    client.set_unknown_jurisdiction_policy(&token, &true);
+    assert!(client.check_jurisdiction(&transfer_context(&german, &unknown, 100, &token)));
+
+    // Allow lists restrict every other country
+    client.configure_allowed_jurisdictions(&token, &vec![&env, String::from_str(&env, "FR")]);
+    assert!(!client.check_jurisdiction(&transfer_context(&german, &german, 100, &token)));
+    client.add_allowed_jurisdiction(&token, &String::from_str(&env, "DE"));
+    assert!(client.check_jurisdiction(&transfer_context(&german, &german, 100, &token)));
+}
+
+#[test]
+fn test_jurisdiction_deny_default_requires_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let contract_id = env.register(JurisdictionModule, ());
+    let client = JurisdictionModuleClient::new(&env, &contract_id);
+    client.init_jurisdiction(&admin);
+
+    // Allowing unknown countries lets the module run without a registry
+    client.set_unknown_jurisdiction_policy(&token, &true);
+    client.enable_for_token(&token);
+    assert!(client.check_jurisdiction(&transfer_context(&holder, &holder, 100, &token)));
+
+    assert!(client.try_set_unknown_jurisdiction_policy(&token, &false).is_err());
+    assert!(client.allows_unknown_jurisdiction(&token));
+}
+
+#[test]
+fn test_jurisdiction_groups_and_transfer_rules() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token = Address::generate(&env);
+    let german = Address::generate(&env);
+    let french = Address::generate(&env);
+    let american = Address::generate(&env);
+
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.set_country(&german, &String::from_str(&env, "DE"));
+    registry.set_country(&french, &String::from_str(&env, "FR"));
+    registry.set_country(&american, &String::from_str(&env, "US"));
+
+    let contract_id = env.register(JurisdictionModule, ());
+    let client = JurisdictionModuleClient::new(&env, &contract_id);
+    client.init_jurisdiction(&admin);
+    client.set_jurisdiction_registry(&token, &registry_id);
+    client.enable_for_token(&token);
+
+    // Only officially assigned ISO 3166 codes are accepted
+    let eu = Symbol::new(&env, "EU");
+    assert!(client.try_set_country_group(&eu, &vec![&env, String::from_str(&env, "UK")]).is_err());
+    assert!(client.try_add_denied_jurisdiction(&token, &String::from_str(&env, "UK")).is_err());
+    assert!(client.try_configure_denied_groups(&token, &vec![&env, Symbol::new(&env, "OFAC")]).is_err());
+    client.set_country_group(&eu, &vec![&env, String::from_str(&env, "DE"), String::from_str(&env, "FR")]);
+
+    // German holders may only transfer within the EU; the rule is one-directional
+    assert!(client.check_jurisdiction(&transfer_context(&german, &american, 100, &token)));
+    client.set_country_transfer_rule(&token, &String::from_str(&env, "DE"), &Vec::new(&env), &vec![&env, eu.clone()]);
+    assert!(!client.check_jurisdiction(&transfer_context(&german, &american, 100, &token)));
+    assert!(client.check_jurisdiction(&transfer_context(&german, &french, 100, &token)));
+    assert!(client.check_jurisdiction(&transfer_context(&american, &german, 100, &token)));
+    assert!(client.is_country_pair_allowed(&token, &String::from_str(&env, "US"), &String::from_str(&env, "DE")));
+    assert!(!client.is_country_pair_allowed(&token, &String::from_str(&env, "DE"), &String::from_str(&env, "US")));
+
+    // Groups expand to their member countries in allow and deny lists
+    client.configure_allowed_groups(&token, &vec![&env, eu.clone()]);
+    assert!(!client.check_jurisdiction(&transfer_context(&american, &german, 100, &token)));
+    assert!(client.check_jurisdiction(&transfer_context(&french, &german, 100, &token)));
+    client.configure_allowed_groups(&token, &Vec::new(&env));
+    client.configure_denied_groups(&token, &vec![&env, eu.clone()]);
+    assert!(!client.check_jurisdiction(&transfer_context(&french, &german, 100, &token)));
+
+    // A referenced group cannot be removed
+    assert_eq!(client.get_group_references(&eu), 2);
+    assert!(client.try_remove_country_group(&eu).is_err());
+    client.configure_denied_groups(&token, &Vec::new(&env));
+    client.remove_country_transfer_rule(&token, &String::from_str(&env, "DE"));
+    assert_eq!(client.get_group_references(&eu), 0);
+
+    client.remove_country_group(&eu);
     assert_eq!(
-        words,
+        env.events().all(),
         vec![
             &env,
-            String::from_str(&env, "Hello"),
-            String::from_str(&env, "Dev"),
+            (contract_id.clone(), (symbol_short!("GROUP_REM"),).into_val(&env), eu.into_val(&env)),
         ]
     );
+    assert!(client.get_country_group(&Symbol::new(&env, "EU")).is_empty());
+}
+
+#[test]
+fn test_overlapping_lockups() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let contract_id = env.register(LockupModule, ());
+    let client = LockupModuleClient::new(&env, &contract_id);
+    client.init_lockup(&admin);
+
+    // A 600 grant vesting linearly over 100..200 and a 400 grant fully
+    // locked until 300
+    assert_eq!(client.create_lockup(&token, &holder, &600i128, &0u64, &100u64, &200u64, &false), 0);
+    assert_eq!(client.create_lockup(&token, &holder, &400i128, &0u64, &300u64, &300u64, &false), 1);
+    assert_eq!(client.get_lockups(&token, &holder).len(), 2);
+    assert_eq!(client.get_lockup(&token, &holder, &1u32).unwrap().total_amount, 400);
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    assert_eq!(client.get_locked_amount(&token, &holder), 1000);
+
+    // The sender's balance after the transfer must cover both lockups
+    let transfer = TransferContext { from_balance: 900, ..transfer_context(&holder, &other, 100, &token) };
+    assert!(!client.check_lockup(&transfer));
+    let transfer = TransferContext { from_balance: 1000, ..transfer_context(&holder, &other, 100, &token) };
+    assert!(client.check_lockup(&transfer));
+
+    // Halfway through the first grant's vesting 300 of it is free
+    env.ledger().with_mut(|li| li.timestamp = 150);
+    assert_eq!(client.get_locked_amount(&token, &holder), 700);
+    let transfer = TransferContext { from_balance: 700, ..transfer_context(&holder, &other, 300, &token) };
+    assert!(client.check_lockup(&transfer));
+    let transfer = TransferContext { from_balance: 699, ..transfer_context(&holder, &other, 301, &token) };
+    assert!(!client.check_lockup(&transfer));
+
+    // Once the first grant has vested only the second still binds
+    env.ledger().with_mut(|li| li.timestamp = 250);
+    assert_eq!(client.get_locked_amount(&token, &holder), 400);
+    assert_eq!(client.release_vested_tokens(&token, &holder), 600);
+    assert_eq!(client.get_locked_amount(&token, &holder), 400);
+
+    env.ledger().with_mut(|li| li.timestamp = 300);
+    assert_eq!(client.get_locked_amount(&token, &holder), 0);
+    let transfer = TransferContext { from_balance: 0, ..transfer_context(&holder, &other, 1000, &token) };
+    assert!(client.check_lockup(&transfer));
+}
+
+#[test]
+fn test_lockup_revocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let token = env.register(MockToken, ());
+    let token_client = MockTokenClient::new(&env, &token);
+    token_client.init_token(&admin);
+    token_client.set_balance(&holder, &1100i128);
+
+    let contract_id = env.register(LockupModule, ());
+    let client = LockupModuleClient::new(&env, &contract_id);
+    client.init_lockup(&admin);
+
+    // 1000 vesting linearly over 0..1000, and a grant that cannot be revoked
+    let lockup_id = client.create_lockup(&token, &holder, &1000i128, &0u64, &0u64, &1000u64, &true);
+    let fixed_id = client.create_lockup(&token, &holder, &100i128, &0u64, &0u64, &1000u64, &false);
+
+    env.ledger().with_mut(|li| li.timestamp = 250);
+    assert!(client.try_revoke_lockup(&token, &holder, &7u32).is_err());
+    assert!(client.try_revoke_lockup(&token, &holder, &fixed_id).is_err());
+    assert!(client.try_revoke_lockup(&token, &holder, &lockup_id).is_err());
+
+    client.set_lockup_treasury(&token, &treasury);
+    client.revoke_lockup(&token, &holder, &lockup_id);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("LOCK_REV"),).into_val(&env),
+                (token.clone(), holder.clone(), lockup_id, 250i128, 750i128).into_val(&env),
+            ),
+        ]
+    );
+    assert!(client.try_revoke_lockup(&token, &holder, &lockup_id).is_err());
+
+    // The unvested 750 is back in the treasury; the vested 250 stays free
+    assert_eq!(token_client.balance(&treasury), 750);
+    assert_eq!(token_client.balance(&holder), 350);
+    assert!(client.get_lockup(&token, &holder, &lockup_id).unwrap().revoked);
+    assert_eq!(client.get_locked_amount(&token, &holder), 75);
+}
+
+#[test]
+fn test_holding_period_lots() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let contract_id = env.register(HoldingPeriodModule, ());
+    let client = HoldingPeriodModuleClient::new(&env, &contract_id);
+    client.init_holding_period(&admin, &compliance);
+    client.set_holding_period(&token, &100u64);
+
+    // 200 held before the module tracked the token, then 100 minted at 0 and 50 at 50
+    client.holding_created(&holder, &100i128, &token, &300i128, &1000i128);
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    client.holding_created(&holder, &50i128, &token, &350i128, &1000i128);
+    assert_eq!(client.get_restricted_amount(&token, &holder), 150);
+
+    // Only the untracked 200 can be sold
+    let sale = TransferContext { from_balance: 150, ..transfer_context(&holder, &buyer, 200, &token) };
+    assert!(client.check_holding_period(&sale));
+    let sale = TransferContext { from_balance: 149, ..transfer_context(&holder, &buyer, 201, &token) };
+    assert!(!client.check_holding_period(&sale));
+
+    // A partial sale uses the untracked balance and leaves every lot intact
+    let sale = TransferContext { from_balance: 230, ..transfer_context(&holder, &buyer, 120, &token) };
+    client.holding_transferred(&sale);
+    let lots = client.get_lots(&token, &holder);
+    assert_eq!(lots.len(), 2);
+    assert_eq!(lots.get(0).unwrap().amount, 100);
+    assert_eq!(client.get_restricted_amount(&token, &holder), 150);
+    assert_eq!(client.get_restricted_amount(&token, &buyer), 120);
+
+    // Past the remaining 80 of untracked balance, sales consume the oldest lot
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    assert_eq!(client.get_restricted_amount(&token, &holder), 50);
+    let sale = TransferContext { from_balance: 50, ..transfer_context(&holder, &buyer, 180, &token) };
+    assert!(client.check_holding_period(&sale));
+    client.holding_transferred(&sale);
+    let lots = client.get_lots(&token, &holder);
+    assert_eq!(lots.len(), 1);
+    assert_eq!(lots.get(0).unwrap().acquired_at, 50);
+
+    // Burns follow the same order
+    client.holding_destroyed(&holder, &50i128, &token, &0i128, &1000i128);
+    assert_eq!(client.get_lots(&token, &holder).len(), 0);
+}
+
+#[test]
+fn test_holding_period_by_jurisdiction() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let domestic = Address::generate(&env);
+    let offshore = Address::generate(&env);
+
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.set_country(&domestic, &String::from_str(&env, "US"));
+    registry.set_country(&offshore, &String::from_str(&env, "JP"));
+
+    let contract_id = env.register(HoldingPeriodModule, ());
+    let client = HoldingPeriodModuleClient::new(&env, &contract_id);
+    client.init_holding_period(&admin, &compliance);
+    client.set_holding_period(&token, &(365 * 86400u64));
+    client.set_holding_period_registry(&token, &registry_id);
+    client.set_country_holding_period(&token, &String::from_str(&env, "JP"), &(40 * 86400u64));
+
+    assert_eq!(client.get_holding_period(&token, &domestic), 365 * 86400);
+    assert_eq!(client.get_holding_period(&token, &offshore), 40 * 86400);
+
+    client.holding_created(&domestic, &100i128, &token, &100i128, &1000i128);
+    client.holding_created(&offshore, &100i128, &token, &100i128, &1000i128);
+
+    env.ledger().with_mut(|li| li.timestamp = 41 * 86400);
+    assert_eq!(client.get_restricted_amount(&token, &domestic), 100);
+    assert_eq!(client.get_restricted_amount(&token, &offshore), 0);
+
+    client.remove_country_holding_period(&token, &String::from_str(&env, "JP"));
+    assert_eq!(client.get_restricted_amount(&token, &offshore), 100);
+}
+
+#[test]
+fn test_holding_period_lot_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let contract_id = env.register(HoldingPeriodModule, ());
+    let client = HoldingPeriodModuleClient::new(&env, &contract_id);
+    client.init_holding_period(&admin, &compliance);
+    client.set_holding_period(&token, &1000u64);
+
+    // One acquisition per second: the oldest lots merge into later ones
+    for second in 0..40u64 {
+        env.ledger().with_mut(|li| li.timestamp = second);
+        client.holding_created(&holder, &10i128, &token, &(10 * (second as i128 + 1)), &1000i128);
+    }
+    let lots = client.get_lots(&token, &holder);
+    assert_eq!(lots.len(), 32);
+    assert_eq!(lots.get(0).unwrap().amount, 90);
+    assert_eq!(lots.get(0).unwrap().acquired_at, 8);
+    assert_eq!(client.get_restricted_amount(&token, &holder), 400);
+
+    // Matured lots are folded into the untracked balance on the next acquisition
+    env.ledger().with_mut(|li| li.timestamp = 1020);
+    client.holding_created(&holder, &10i128, &token, &410i128, &1000i128);
+    let lots = client.get_lots(&token, &holder);
+    assert_eq!(lots.len(), 20);
+    assert_eq!(client.get_restricted_amount(&token, &holder), 200);
+}
+
+#[test]
+fn test_max_holders_follow_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let wallet1 = Address::generate(&env);
+    let wallet2 = Address::generate(&env);
+    let other = Address::generate(&env);
+    let newcomer = Address::generate(&env);
+
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.link_wallet(&wallet1, &investor);
+    registry.link_wallet(&wallet2, &investor);
+
+    let contract_id = env.register(MaxHoldersModule, ());
+    let client = MaxHoldersModuleClient::new(&env, &contract_id);
+    client.init_max_holders(&admin);
+    client.set_max_holders_compliance(&compliance);
+    client.set_max_holders_registry(&token, &registry_id);
+    client.set_max_holders(&token, &2u32);
+
+    client.created(&wallet1, &100i128, &token, &100i128, &1000i128);
+    assert!(client.is_holder(&token, &investor));
+    assert_eq!(client.get_holder_count(&token), 1);
+
+    // Splitting across the investor's own wallets keeps one holder
+    let context = TransferContext { from_balance: 50, ..transfer_context(&wallet1, &wallet2, 50, &token) };
+    client.transferred(&context);
+    assert_eq!(client.get_holder_count(&token), 1);
+
+    // Emptying one wallet leaves the investor counted through the other
+    client.transferred(&transfer_context(&wallet1, &other, 50, &token));
+    assert!(client.is_holder(&token, &investor));
+    assert_eq!(client.get_holder_count(&token), 2);
+    assert!(!client.check_max_holders(&transfer_context(&other, &newcomer, 10, &token)));
+
+    // A sender going to zero frees the slot
+    let context = TransferContext { to_balance: 100, ..transfer_context(&wallet2, &other, 50, &token) };
+    client.transferred(&context);
+    assert!(!client.is_holder(&token, &investor));
+    assert_eq!(client.get_holder_count(&token), 1);
+    assert!(client.check_max_holders(&transfer_context(&other, &newcomer, 10, &token)));
+
+    // A partial burn keeps the holder, a clawback of the rest removes them
+    client.destroyed(&other, &40i128, &token, &60i128, &1000i128);
+    assert!(client.is_holder(&token, &other));
+    client.destroyed(&other, &60i128, &token, &0i128, &1000i128);
+    assert!(!client.is_holder(&token, &other));
+    assert_eq!(client.get_holder_count(&token), 0);
+}
+
+#[test]
+fn test_max_holders_category_and_country_caps() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let accredited = Address::generate(&env);
+    let retail_us = Address::generate(&env);
+    let retail_fr = Address::generate(&env);
+    let accredited_us = Address::generate(&env);
+
+    let us = String::from_str(&env, "US");
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.set_topic(&accredited, &3u32, &true);
+    registry.set_topic(&accredited_us, &3u32, &true);
+    registry.set_country(&accredited, &String::from_str(&env, "DE"));
+    registry.set_country(&retail_us, &us);
+    registry.set_country(&retail_fr, &String::from_str(&env, "FR"));
+    registry.set_country(&accredited_us, &us);
+
+    let contract_id = env.register(MaxHoldersModule, ());
+    let client = MaxHoldersModuleClient::new(&env, &contract_id);
+    client.init_max_holders(&admin);
+    client.set_max_holders_compliance(&compliance);
+    client.set_max_holders_registry(&token, &registry_id);
+
+    let accred = symbol_short!("ACCRED");
+    let retail = symbol_short!("RETAIL");
+    client.set_holder_categories(&token, &vec![&env, HolderCategory { category: accred.clone(), topic_id: 3 }], &retail);
+    client.set_holder_category_cap(&token, &retail, &1u32);
+    client.set_holder_country_cap(&token, &us, &1u32);
+    assert_eq!(client.get_holder_info(&token, &accredited).category, accred);
+
+    client.created(&retail_us, &100i128, &token, &100i128, &1000i128);
+    assert_eq!(client.get_holder_category_count(&token, &retail), 1);
+    assert_eq!(client.get_holder_country_count(&token, &us), 1);
+
+    // The retail cap is reached, accredited investors still get in
+    assert!(!client.check_max_holders(&transfer_context(&issuer, &retail_fr, 10, &token)));
+    assert!(client.check_max_holders(&transfer_context(&issuer, &accredited, 10, &token)));
+    client.created(&accredited, &100i128, &token, &100i128, &1000i128);
+
+    // The US cap applies across categories
+    assert!(!client.check_max_holders(&transfer_context(&issuer, &accredited_us, 10, &token)));
+
+    // A holder who became accredited leaves under the category they joined in
+    registry.set_topic(&retail_us, &3u32, &true);
+    client.destroyed(&retail_us, &100i128, &token, &0i128, &1000i128);
+    assert_eq!(client.get_holder_category_count(&token, &retail), 0);
+    assert_eq!(client.get_holder_category_count(&token, &accred), 1);
+    assert_eq!(client.get_holder_country_count(&token, &us), 0);
+    assert!(client.check_max_holders(&transfer_context(&issuer, &retail_fr, 10, &token)));
+    assert!(client.check_max_holders(&transfer_context(&issuer, &accredited_us, 10, &token)));
+}
+
+#[test]
+fn test_max_holders_wallet_linked_after_funding() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let wallet1 = Address::generate(&env);
+    let wallet2 = Address::generate(&env);
+
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.link_wallet(&wallet1, &investor);
+
+    let contract_id = env.register(MaxHoldersModule, ());
+    let client = MaxHoldersModuleClient::new(&env, &contract_id);
+    client.init_max_holders(&admin);
+    client.set_max_holders_compliance(&compliance);
+    client.set_max_holders_registry(&token, &registry_id);
+
+    // wallet2 is funded before it is linked, so it counts on its own
+    client.created(&wallet1, &100i128, &token, &100i128, &1000i128);
+    client.created(&wallet2, &100i128, &token, &100i128, &1000i128);
+    assert!(client.is_holder(&token, &wallet2));
+    assert_eq!(client.get_holder_count(&token), 2);
+
+    // Its next balance update moves the count over to the investor
+    registry.link_wallet(&wallet2, &investor);
+    client.destroyed(&wallet2, &40i128, &token, &60i128, &1000i128);
+    assert!(!client.is_holder(&token, &wallet2));
+    assert!(client.is_holder(&token, &investor));
+    assert_eq!(client.get_holder_count(&token), 1);
+
+    // The investor stays a holder until both wallets are empty
+    client.destroyed(&wallet1, &100i128, &token, &0i128, &1000i128);
+    assert!(client.is_holder(&token, &investor));
+    client.destroyed(&wallet2, &60i128, &token, &0i128, &1000i128);
+    assert!(!client.is_holder(&token, &investor));
+    assert_eq!(client.get_holder_count(&token), 0);
+}
+
+#[test]
+fn test_max_holders_bookkeeping_requires_compliance() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let token = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let contract_id = env.register(MaxHoldersModule, ());
+    let client = MaxHoldersModuleClient::new(&env, &contract_id);
+    client.init_max_holders(&admin);
+    client
+        .mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "set_max_holders_compliance",
+                args: (compliance.clone(),).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .set_max_holders_compliance(&compliance);
+
+    // Only the compliance contract may add or remove holders directly
+    let args: Vec<soroban_sdk::Val> = (token.clone(), holder.clone()).into_val(&env);
+    let add_invoke = MockAuthInvoke {
+        contract: &contract_id,
+        fn_name: "add_holder",
+        args: args.clone(),
+        sub_invokes: &[],
+    };
+    assert!(client
+        .mock_auths(&[MockAuth { address: &outsider, invoke: &add_invoke }])
+        .try_add_holder(&token, &holder)
+        .is_err());
+    client
+        .mock_auths(&[MockAuth { address: &compliance, invoke: &add_invoke }])
+        .add_holder(&token, &holder);
+    assert_eq!(client.get_holder_count(&token), 1);
+
+    let remove_invoke = MockAuthInvoke {
+        contract: &contract_id,
+        fn_name: "remove_holder",
+        args,
+        sub_invokes: &[],
+    };
+    assert!(client
+        .mock_auths(&[MockAuth { address: &outsider, invoke: &remove_invoke }])
+        .try_remove_holder(&token, &holder)
+        .is_err());
+    client
+        .mock_auths(&[MockAuth { address: &compliance, invoke: &remove_invoke }])
+        .remove_holder(&token, &holder);
+    assert_eq!(client.get_holder_count(&token), 0);
+}
+
+#[test]
+fn test_transfer_limits_rolling_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let pool = Address::generate(&env);
+
+    let contract_id = env.register(TransferLimitsModule, ());
+    let client = TransferLimitsModuleClient::new(&env, &contract_id);
+    client.init_transfer_limits(&admin, &compliance);
+
+    let daily = VolumeLimit { window_length: 86400, unit: WindowUnit::Seconds, address_limit: 100, token_limit: 150 };
+    client.set_volume_limits(&token, &vec![&env, daily.clone()]);
+
+    // Per-sender cap
+    client.transfer_limits_transferred(&transfer_context(&alice, &bob, 80, &token));
+    assert_eq!(client.get_address_volume(&token, &0u32, &alice), 80);
+    assert!(client.check_transfer_limits(&transfer_context(&alice, &bob, 20, &token)));
+    assert!(!client.check_transfer_limits(&transfer_context(&alice, &bob, 21, &token)));
+
+    // Per-token cap across senders
+    env.ledger().with_mut(|li| li.timestamp = 43200);
+    client.transfer_limits_transferred(&transfer_context(&bob, &alice, 60, &token));
+    assert_eq!(client.get_token_volume(&token, &0u32), 140);
+    assert!(!client.check_transfer_limits(&transfer_context(&bob, &alice, 11, &token)));
+
+    // Exempt senders are neither limited nor counted
+    client.set_volume_exempt(&token, &pool, &true);
+    assert!(client.check_transfer_limits(&transfer_context(&pool, &alice, 1000, &token)));
+    client.transfer_limits_transferred(&transfer_context(&pool, &alice, 1000, &token));
+    assert_eq!(client.get_token_volume(&token, &0u32), 140);
+
+    // Alice's volume leaves the window a day later, Bob's is still in it
+    env.ledger().with_mut(|li| li.timestamp = 86400);
+    assert_eq!(client.get_address_volume(&token, &0u32, &alice), 0);
+    assert_eq!(client.get_token_volume(&token, &0u32), 60);
+    assert!(client.check_transfer_limits(&transfer_context(&alice, &bob, 90, &token)));
+    assert!(!client.check_transfer_limits(&transfer_context(&alice, &bob, 91, &token)));
+
+    // Re-setting the same window keeps its counters; a new window starts empty
+    let weekly = VolumeLimit { window_length: 7 * 86400, unit: WindowUnit::Seconds, address_limit: 500, token_limit: 0 };
+    client.set_volume_limits(&token, &vec![&env, weekly.clone(), daily.clone()]);
+    assert_eq!(client.get_token_volume(&token, &1u32), 60);
+    assert_eq!(client.get_address_volume(&token, &0u32, &bob), 0);
+    assert!(client.try_set_volume_limits(&token, &vec![&env, daily.clone(), daily]).is_err());
+
+    // Windows must split evenly into the 24 buckets
+    let uneven = VolumeLimit { window_length: 86400 + 1, ..weekly.clone() };
+    assert!(client.try_set_volume_limits(&token, &vec![&env, uneven]).is_err());
+    let short = VolumeLimit { window_length: 12, ..weekly };
+    assert!(client.try_set_volume_limits(&token, &vec![&env, short]).is_err());
+}
+
+#[test]
+fn test_concentration_per_investor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let wallet1 = Address::generate(&env);
+    let wallet2 = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.link_wallet(&wallet1, &investor);
+    registry.link_wallet(&wallet2, &investor);
+
+    let contract_id = env.register(ConcentrationModule, ());
+    let client = ConcentrationModuleClient::new(&env, &contract_id);
+    client.init_concentration(&admin, &compliance);
+    client.set_concentration_registry(&token, &registry_id);
+    client.set_concentration_limit(&token, &1000u32);
+    assert!(client.try_set_concentration_limit(&token, &10_001u32).is_err());
+
+    // Issuance to the exempt treasury may exceed the cap
+    assert!(client.try_concentration_created(&treasury, &1000i128, &token, &1000i128, &1000i128).is_err());
+    client.set_concentration_exempt(&token, &treasury, &true);
+    client.concentration_created(&treasury, &1000i128, &token, &1000i128, &1000i128);
+
+    // A mint within 10% of the new supply is accepted, one above it reverts
+    client.concentration_created(&wallet1, &60i128, &token, &60i128, &1060i128);
+    assert!(client.try_concentration_created(&wallet1, &60i128, &token, &120i128, &1120i128).is_err());
+    assert_eq!(client.get_investor_holding(&token, &wallet1), 60);
+
+    // The investor's wallets are added up against the cap
+    let context = TransferContext {
+        from_balance: 960,
+        to_balance: 40,
+        total_supply: 1060,
+        ..transfer_context(&treasury, &wallet2, 40, &token)
+    };
+    assert!(client.check_concentration(&context));
+    client.concentration_transferred(&context);
+    assert_eq!(client.get_investor_holding(&token, &wallet2), 100);
+
+    let context = TransferContext {
+        from_balance: 953,
+        to_balance: 47,
+        total_supply: 1060,
+        ..transfer_context(&treasury, &wallet2, 7, &token)
+    };
+    assert!(!client.check_concentration(&context));
+
+    // Moving between the investor's own wallets is always allowed
+    let context = TransferContext {
+        from_balance: 0,
+        to_balance: 100,
+        total_supply: 1060,
+        ..transfer_context(&wallet1, &wallet2, 60, &token)
+    };
+    assert!(client.check_concentration(&context));
+    client.concentration_transferred(&context);
+    assert_eq!(client.get_investor_holding(&token, &wallet1), 100);
+
+    // A burn frees room under the cap; exempting the investor lifts it
+    client.concentration_destroyed(&wallet2, &50i128, &token, &50i128, &1010i128);
+    assert_eq!(client.get_investor_holding(&token, &wallet2), 50);
+    let context = TransferContext {
+        from_balance: 910,
+        to_balance: 50,
+        total_supply: 1010,
+        ..transfer_context(&treasury, &wallet1, 50, &token)
+    };
+    assert!(client.check_concentration(&context));
+    let context = TransferContext { amount: 100, from_balance: 810, to_balance: 100, ..context };
+    assert!(!client.check_concentration(&context));
+    client.set_concentration_exempt(&token, &investor, &true);
+    assert!(client.check_concentration(&context));
+
+    // Unrelated holders are capped on their own
+    let context = TransferContext {
+        from_balance: 899,
+        to_balance: 101,
+        total_supply: 1010,
+        ..transfer_context(&treasury, &other, 101, &token)
+    };
+    assert!(client.check_concentration(&context));
+    client.set_concentration_limit(&token, &999u32);
+    assert!(!client.check_concentration(&context));
+}
+
+#[test]
+fn test_investment_limit_tiers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let accredited = Address::generate(&env);
+    let sophisticated = Address::generate(&env);
+    let retail = Address::generate(&env);
+
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.set_topic(&accredited, &3u32, &true);
+    registry.set_topic(&accredited, &5u32, &true);
+    registry.set_topic(&sophisticated, &5u32, &true);
+
+    let contract_id = env.register(InvestmentLimitsModule, ());
+    let client = InvestmentLimitsModuleClient::new(&env, &contract_id);
+    client.init_investment_limits(&admin, &compliance);
+    client.set_investment_registry(&token, &registry_id);
+
+    // Without tiers nobody is limited
+    assert_eq!(client.get_investment_limit(&token, &retail), None);
+    assert!(client.check_investment_limit(&transfer_context(&seller, &retail, 1_000_000, &token)));
+
+    // The first matching tier wins, so accredited investors stay unlimited
+    let tiers = vec![
+        &env,
+        InvestmentTier { topic_id: 3, limit: None },
+        InvestmentTier { topic_id: 5, limit: Some(10_000) },
+    ];
+    assert!(client.try_set_investment_tiers(&token, &tiers, &Some(-1i128)).is_err());
+    client.set_investment_tiers(&token, &tiers, &Some(1_000i128));
+    assert_eq!(client.get_investment_limit(&token, &accredited), None);
+    assert_eq!(client.get_investment_limit(&token, &sophisticated), Some(10_000));
+    assert_eq!(client.remaining_headroom(&token, &accredited), None);
+    assert_eq!(client.remaining_headroom(&token, &sophisticated), Some(10_000));
+
+    // Everyone else falls back to the default limit
+    assert_eq!(client.get_investment_limit(&token, &retail), Some(1_000));
+    assert!(client.check_investment_limit(&transfer_context(&seller, &retail, 1_000, &token)));
+    assert!(!client.check_investment_limit(&transfer_context(&seller, &retail, 1_001, &token)));
+    assert!(client.check_investment_limit(&transfer_context(&seller, &accredited, 1_000_000, &token)));
+}
+
+#[test]
+fn test_investment_limit_rolling_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let compliance = Address::generate(&env);
+    let token = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let retail = Address::generate(&env);
+    let retail_wallet = Address::generate(&env);
+
+    let registry_id = env.register(MockIdentityRegistry, ());
+    let registry = MockIdentityRegistryClient::new(&env, &registry_id);
+    registry.link_wallet(&retail_wallet, &retail);
+
+    let contract_id = env.register(InvestmentLimitsModule, ());
+    let client = InvestmentLimitsModuleClient::new(&env, &contract_id);
+    client.init_investment_limits(&admin, &compliance);
+    client.set_investment_registry(&token, &registry_id);
+    client.set_investment_tiers(&token, &Vec::new(&env), &Some(1_000i128));
+    assert_eq!(client.get_investment_window(&token), 365);
+    assert!(client.try_set_investment_window(&token, &0u64).is_err());
+
+    let day = 86_400u64;
+
+    // Mints and purchases count towards the identity across its wallets
+    client.investment_created(&retail, &600i128, &token, &600i128, &600i128);
+    env.ledger().with_mut(|li| li.timestamp = 100 * day);
+    client.investment_transferred(&transfer_context(&seller, &retail_wallet, 300, &token));
+    assert_eq!(client.get_invested_amount(&token, &retail), 900);
+    assert_eq!(client.remaining_headroom(&token, &retail_wallet), Some(100));
+    assert!(!client.check_investment_limit(&transfer_context(&seller, &retail, 101, &token)));
+
+    // Sales and moves between the investor's own wallets change nothing
+    client.investment_transferred(&transfer_context(&retail, &seller, 500, &token));
+    client.investment_transferred(&transfer_context(&retail, &retail_wallet, 100, &token));
+    assert!(client.check_investment_limit(&transfer_context(&retail, &retail_wallet, 5_000, &token)));
+    assert_eq!(client.get_invested_amount(&token, &retail), 900);
+
+    // A mint over the limit reverts
+    assert!(client.try_investment_created(&retail, &101i128, &token, &101i128, &1_000i128).is_err());
+
+    // The first purchase counts through the window's last day and leaves it
+    // on the 365th day after it
+    env.ledger().with_mut(|li| li.timestamp = 364 * day + day - 1);
+    assert_eq!(client.get_invested_amount(&token, &retail), 900);
+    env.ledger().with_mut(|li| li.timestamp = 365 * day);
+    assert_eq!(client.get_invested_amount(&token, &retail), 300);
+    assert_eq!(client.remaining_headroom(&token, &retail), Some(700));
+
+    // Recording an inflow drops the days that have left the window
+    env.ledger().with_mut(|li| li.timestamp = 465 * day);
+    client.investment_created(&retail, &50i128, &token, &50i128, &1_000i128);
+    assert_eq!(client.get_invested_amount(&token, &retail), 50);
+    let inflows: Vec<InflowRecord> = env.as_contract(&contract_id, || {
+        env.storage().persistent().get(&(symbol_short!("INFLOWS"), token.clone(), retail.clone())).unwrap()
+    });
+    assert_eq!(inflows, vec![&env, InflowRecord { day: 465, amount: 50 }]);
+
+    // Treasury mints would exceed the default limit unless it is exempt
+    assert!(client.try_investment_created(&treasury, &1_000_000i128, &token, &1_000_000i128, &1_000_000i128).is_err());
+    client.set_investment_exempt(&token, &treasury, &true);
+    client.investment_created(&treasury, &1_000_000i128, &token, &1_000_000i128, &1_000_000i128);
+    assert_eq!(client.get_invested_amount(&token, &treasury), 0);
+    assert!(client.check_investment_limit(&transfer_context(&seller, &treasury, 1_000_000, &token)));
+
+    // Exempting the investor covers all of its wallets
+    assert!(!client.check_investment_limit(&transfer_context(&seller, &retail_wallet, 1_000, &token)));
+    client.set_investment_exempt(&token, &retail, &true);
+    assert!(client.check_investment_limit(&transfer_context(&seller, &retail_wallet, 1_000, &token)));
 }
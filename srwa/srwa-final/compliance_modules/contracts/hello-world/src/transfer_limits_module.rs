@@ -0,0 +1,243 @@
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec
+};
+use crate::TransferContext;
+
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const COMPLIANCE: Symbol = symbol_short!("COMPLY");
+const VOLUME_LIMITS: Symbol = symbol_short!("VOL_LIM");
+const ADDRESS_VOLUME: Symbol = symbol_short!("VOL_ADDR");
+const TOKEN_VOLUME: Symbol = symbol_short!("VOL_TOK");
+const EXEMPT: Symbol = symbol_short!("VOL_EXMPT");
+
+// Each window is tracked in this many buckets; volume leaves the window one
+// bucket at a time
+const BUCKETS_PER_WINDOW: u64 = 24;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum WindowUnit {
+    Seconds,
+    Ledgers,
+}
+
+// Rolling window limit; a limit of 0 leaves that side unrestricted
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct VolumeLimit {
+    pub window_length: u64,
+    pub unit: WindowUnit,
+    pub address_limit: i128,
+    pub token_limit: i128,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct VolumeBucket {
+    pub bucket: u64,
+    pub amount: i128,
+}
+
+// Caps how much each sender, and the token as a whole, can move within
+// rolling windows (e.g. per day and per month). Exempt addresses, such as
+// authorized liquidity pools, are neither limited nor counted as senders.
+#[contract]
+pub struct TransferLimitsModule;
+
+#[contractimpl]
+impl TransferLimitsModule {
+    // The transferred hook only accepts calls from the compliance contract
+    pub fn init_transfer_limits(env: Env, admin: Address, compliance: Address) {
+        if env.storage().instance().has(&ADMIN) {
+            panic!("Already initialized");
+        }
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&COMPLIANCE, &compliance);
+    }
+
+    // Replaces the token's limits. Counters are kept per window (length and
+    // unit), so a window that stays configured keeps its volume while a new
+    // or resized one starts empty
+    pub fn set_volume_limits(env: Env, token: Address, limits: Vec<VolumeLimit>) {
+        Self::require_admin(&env);
+
+        for (index, limit) in limits.iter().enumerate() {
+            if limit.window_length < BUCKETS_PER_WINDOW {
+                panic!("Window too short");
+            }
+            // Buckets must tile the window exactly, or it would be shorter
+            // than configured
+            if limit.window_length % BUCKETS_PER_WINDOW != 0 {
+                panic!("Window not a multiple of the bucket count");
+            }
+            if limit.address_limit < 0 || limit.token_limit < 0 {
+                panic!("Negative limit");
+            }
+            // Two limits on one window would share, and double count, its counters
+            if limits.iter().skip(index + 1).any(|other| {
+                other.window_length == limit.window_length && other.unit == limit.unit
+            }) {
+                panic!("Duplicate window");
+            }
+        }
+
+        let key = (VOLUME_LIMITS, token.clone());
+        env.storage().persistent().set(&key, &limits);
+
+        env.events().publish(
+            (symbol_short!("VOL_SET"),),
+            (token, limits.len())
+        );
+    }
+
+    pub fn get_volume_limits(env: Env, token: Address) -> Vec<VolumeLimit> {
+        let key = (VOLUME_LIMITS, token);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn set_volume_exempt(env: Env, token: Address, address: Address, exempt: bool) {
+        Self::require_admin(&env);
+        let key = (EXEMPT, token.clone(), address.clone());
+        if exempt {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events().publish(
+            (symbol_short!("VOL_EXMPT"),),
+            (token, address, exempt)
+        );
+    }
+
+    pub fn is_volume_exempt(env: Env, token: Address, address: Address) -> bool {
+        let key = (EXEMPT, token, address);
+        env.storage().persistent().get(&key).unwrap_or(false)
+    }
+
+    // Volume sent by `address` within the current window of the limit at `index`
+    pub fn get_address_volume(env: Env, token: Address, index: u32, address: Address) -> i128 {
+        let limit = Self::limit_at(&env, &token, index);
+        let key = Self::address_key(&token, &limit, &address);
+        Self::window_volume(&env, &limit, &key)
+    }
+
+    pub fn get_token_volume(env: Env, token: Address, index: u32) -> i128 {
+        let limit = Self::limit_at(&env, &token, index);
+        let key = Self::token_key(&token, &limit);
+        Self::window_volume(&env, &limit, &key)
+    }
+
+    pub fn check_transfer_limits(env: Env, context: TransferContext) -> bool {
+        if Self::is_volume_exempt(env.clone(), context.token.clone(), context.from.clone()) {
+            return true;
+        }
+
+        for limit in Self::get_volume_limits(env.clone(), context.token.clone()).iter() {
+            if limit.address_limit > 0 {
+                let key = Self::address_key(&context.token, &limit, &context.from);
+                if Self::window_volume(&env, &limit, &key) + context.amount > limit.address_limit {
+                    return false;
+                }
+            }
+
+            if limit.token_limit > 0 {
+                let key = Self::token_key(&context.token, &limit);
+                if Self::window_volume(&env, &limit, &key) + context.amount > limit.token_limit {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn transfer_limits_transferred(env: Env, context: TransferContext) {
+        Self::require_compliance(&env);
+
+        if context.amount <= 0
+            || Self::is_volume_exempt(env.clone(), context.token.clone(), context.from.clone())
+        {
+            return;
+        }
+
+        for limit in Self::get_volume_limits(env.clone(), context.token.clone()).iter() {
+            let address_key = Self::address_key(&context.token, &limit, &context.from);
+            Self::record_volume(&env, &limit, &address_key, context.amount);
+
+            let token_key = Self::token_key(&context.token, &limit);
+            Self::record_volume(&env, &limit, &token_key, context.amount);
+        }
+    }
+
+    fn address_key(token: &Address, limit: &VolumeLimit, address: &Address) -> (Symbol, Address, u64, WindowUnit, Address) {
+        (ADDRESS_VOLUME, token.clone(), limit.window_length, limit.unit.clone(), address.clone())
+    }
+
+    fn token_key(token: &Address, limit: &VolumeLimit) -> (Symbol, Address, u64, WindowUnit) {
+        (TOKEN_VOLUME, token.clone(), limit.window_length, limit.unit.clone())
+    }
+
+    fn limit_at(env: &Env, token: &Address, index: u32) -> VolumeLimit {
+        Self::get_volume_limits(env.clone(), token.clone())
+            .get(index)
+            .unwrap_or_else(|| panic!("Limit not found"))
+    }
+
+    fn current_bucket(env: &Env, limit: &VolumeLimit) -> u64 {
+        let now = match limit.unit {
+            WindowUnit::Seconds => env.ledger().timestamp(),
+            WindowUnit::Ledgers => env.ledger().sequence() as u64,
+        };
+        now / (limit.window_length / BUCKETS_PER_WINDOW)
+    }
+
+    fn window_volume<K>(env: &Env, limit: &VolumeLimit, key: &K) -> i128
+    where
+        K: soroban_sdk::IntoVal<Env, soroban_sdk::Val>,
+    {
+        let current = Self::current_bucket(env, limit);
+        let buckets: Vec<VolumeBucket> = env.storage().persistent().get(key).unwrap_or_else(|| Vec::new(env));
+
+        buckets.iter()
+            .filter(|bucket| bucket.bucket + BUCKETS_PER_WINDOW > current)
+            .map(|bucket| bucket.amount)
+            .sum()
+    }
+
+    fn record_volume<K>(env: &Env, limit: &VolumeLimit, key: &K, amount: i128)
+    where
+        K: soroban_sdk::IntoVal<Env, soroban_sdk::Val>,
+    {
+        let current = Self::current_bucket(env, limit);
+        let stored: Vec<VolumeBucket> = env.storage().persistent().get(key).unwrap_or_else(|| Vec::new(env));
+
+        // Drop buckets that have left the window
+        let mut buckets = Vec::new(env);
+        for bucket in stored.iter() {
+            if bucket.bucket + BUCKETS_PER_WINDOW > current {
+                buckets.push_back(bucket);
+            }
+        }
+
+        match buckets.last() {
+            Some(mut last) if last.bucket == current => {
+                last.amount += amount;
+                buckets.set(buckets.len() - 1, last);
+            }
+            _ => buckets.push_back(VolumeBucket { bucket: current, amount }),
+        }
+
+        env.storage().persistent().set(key, &buckets);
+    }
+
+    fn require_compliance(env: &Env) {
+        let compliance: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
+        compliance.require_auth();
+    }
+
+    fn require_admin(env: &Env) {
+        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        admin.require_auth();
+    }
+}
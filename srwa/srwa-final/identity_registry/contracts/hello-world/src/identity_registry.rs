@@ -0,0 +1,329 @@
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, vec, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Vec
+};
+use crate::{Identity, TopicConfig};
+
+// Separate from the storage contract's keys: both contracts ship in the same wasm
+const ADMIN: Symbol = symbol_short!("IR_ADMIN");
+const IDENTITY_STORAGE: Symbol = symbol_short!("ID_STORE");
+const CLAIM_TOPICS_REGISTRY: Symbol = symbol_short!("CLM_TOPIC");
+const TRUSTED_ISSUERS_REGISTRY: Symbol = symbol_short!("TRUST_ISS");
+const IDENTITIES: Symbol = symbol_short!("IDENTITY");
+const LINKED_TO: Symbol = symbol_short!("LINKED_TO");
+const LINKED_WALLETS: Symbol = symbol_short!("WALLETS");
+
+const RESIDENCY_TOPIC: u32 = 4;
+
+// Maps investor wallets to identities and countries, and answers whether a
+// wallet holds valid claims for every required topic from trusted issuers.
+// The IdentityRegistryStorage it points to must be a separate deployment.
+//
+// An identity is registered under its primary wallet (Identity.holder), which
+// acts as the identity admin and is the subject of its claims. Further wallets
+// (custody, trading, collateral) can be linked to it; every lookup made with a
+// linked wallet resolves to the primary one.
+#[contract]
+pub struct IdentityRegistry;
+
+#[contractimpl]
+impl IdentityRegistry {
+    pub fn init_identity_registry(
+        env: Env,
+        admin: Address,
+        identity_storage: Address,
+        claim_topics_registry: Address,
+        trusted_issuers_registry: Address,
+    ) {
+        if env.storage().instance().has(&ADMIN) {
+            panic!("Already initialized");
+        }
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&IDENTITY_STORAGE, &identity_storage);
+        env.storage().instance().set(&CLAIM_TOPICS_REGISTRY, &claim_topics_registry);
+        env.storage().instance().set(&TRUSTED_ISSUERS_REGISTRY, &trusted_issuers_registry);
+    }
+
+    pub fn register_identity(env: Env, wallet: Address, identity_id: BytesN<32>, country: String) {
+        Self::require_admin(&env);
+        Self::register_identity_internal(&env, &wallet, &identity_id, &country);
+    }
+
+    pub fn batch_register_identity(
+        env: Env,
+        wallets: Vec<Address>,
+        identity_ids: Vec<BytesN<32>>,
+        countries: Vec<String>,
+    ) {
+        Self::require_admin(&env);
+
+        if wallets.len() != identity_ids.len() || wallets.len() != countries.len() {
+            panic!("Length mismatch");
+        }
+
+        for i in 0..wallets.len() {
+            Self::register_identity_internal(
+                &env,
+                &wallets.get(i).unwrap(),
+                &identity_ids.get(i).unwrap(),
+                &countries.get(i).unwrap(),
+            );
+        }
+    }
+
+    pub fn update_identity(env: Env, wallet: Address, identity_id: BytesN<32>) {
+        Self::require_admin(&env);
+
+        let key = (IDENTITIES, wallet.clone());
+        let mut identity: Identity = env.storage().persistent().get(&key).expect("Identity not found");
+        identity.identity_id = identity_id.clone();
+        env.storage().persistent().set(&key, &identity);
+
+        env.events().publish(
+            (symbol_short!("ID_UPD"),),
+            (wallet, identity_id)
+        );
+    }
+
+    pub fn update_country(env: Env, wallet: Address, country: String) {
+        Self::require_admin(&env);
+        Self::validate_country(&country);
+
+        let key = (IDENTITIES, wallet.clone());
+        let mut identity: Identity = env.storage().persistent().get(&key).expect("Identity not found");
+        identity.country = country.clone();
+        env.storage().persistent().set(&key, &identity);
+
+        env.events().publish(
+            (symbol_short!("CTRY_UPD"),),
+            (wallet, country)
+        );
+    }
+
+    pub fn delete_identity(env: Env, wallet: Address) {
+        Self::require_admin(&env);
+
+        let key = (IDENTITIES, wallet.clone());
+        let identity: Identity = env.storage().persistent().get(&key).expect("Identity not found");
+        env.storage().persistent().remove(&key);
+
+        let wallets_key = (LINKED_WALLETS, wallet.clone());
+        let linked: Vec<Address> = env.storage().persistent().get(&wallets_key).unwrap_or_else(|| Vec::new(&env));
+        for linked_wallet in linked.iter() {
+            env.storage().persistent().remove(&(LINKED_TO, linked_wallet));
+        }
+        env.storage().persistent().remove(&wallets_key);
+
+        env.events().publish(
+            (symbol_short!("ID_DEL"),),
+            (wallet, identity.identity_id)
+        );
+    }
+
+    // Linking requires both the identity admin (primary wallet) and the wallet being linked
+    pub fn link_wallet(env: Env, holder: Address, wallet: Address) {
+        holder.require_auth();
+        wallet.require_auth();
+
+        if !env.storage().persistent().has(&(IDENTITIES, holder.clone())) {
+            panic!("Identity not found");
+        }
+        if env.storage().persistent().has(&(IDENTITIES, wallet.clone()))
+            || env.storage().persistent().has(&(LINKED_TO, wallet.clone()))
+        {
+            panic!("Wallet already belongs to an identity");
+        }
+
+        env.storage().persistent().set(&(LINKED_TO, wallet.clone()), &holder);
+
+        let wallets_key = (LINKED_WALLETS, holder.clone());
+        let mut linked: Vec<Address> = env.storage().persistent().get(&wallets_key).unwrap_or_else(|| Vec::new(&env));
+        linked.push_back(wallet.clone());
+        env.storage().persistent().set(&wallets_key, &linked);
+
+        env.events().publish(
+            (symbol_short!("WAL_LINK"),),
+            (holder, wallet)
+        );
+    }
+
+    pub fn unlink_wallet(env: Env, holder: Address, wallet: Address) {
+        holder.require_auth();
+        wallet.require_auth();
+
+        let link_key = (LINKED_TO, wallet.clone());
+        let linked_holder: Option<Address> = env.storage().persistent().get(&link_key);
+        if linked_holder != Some(holder.clone()) {
+            panic!("Wallet not linked to identity");
+        }
+        env.storage().persistent().remove(&link_key);
+
+        let wallets_key = (LINKED_WALLETS, holder.clone());
+        let mut linked: Vec<Address> = env.storage().persistent().get(&wallets_key).unwrap_or_else(|| Vec::new(&env));
+        if let Some(index) = linked.first_index_of(&wallet) {
+            linked.remove(index);
+            env.storage().persistent().set(&wallets_key, &linked);
+        }
+
+        env.events().publish(
+            (symbol_short!("WAL_UNL"),),
+            (holder, wallet)
+        );
+    }
+
+    // Primary wallet of the identity a wallet belongs to, or the wallet itself
+    // when it is not linked. Compliance modules key investors on this address.
+    pub fn resolve_investor(env: Env, wallet: Address) -> Address {
+        env.storage().persistent().get(&(LINKED_TO, wallet.clone())).unwrap_or(wallet)
+    }
+
+    // Every wallet of the identity, primary first
+    pub fn get_linked_wallets(env: Env, wallet: Address) -> Vec<Address> {
+        let holder = Self::resolve_investor(env.clone(), wallet);
+        let mut wallets = Vec::new(&env);
+        wallets.push_back(holder.clone());
+
+        let linked: Vec<Address> = env.storage().persistent()
+            .get(&(LINKED_WALLETS, holder))
+            .unwrap_or_else(|| Vec::new(&env));
+        wallets.append(&linked);
+        wallets
+    }
+
+    pub fn get_identity(env: Env, wallet: Address) -> Option<Identity> {
+        let key = (IDENTITIES, Self::resolve_investor(env.clone(), wallet));
+        env.storage().persistent().get(&key)
+    }
+
+    pub fn is_registered(env: Env, wallet: Address) -> bool {
+        let key = (IDENTITIES, Self::resolve_investor(env.clone(), wallet));
+        env.storage().persistent().has(&key)
+    }
+
+    // A disclosed residency claim (topic 4, attribute "country") takes
+    // precedence over the country recorded at registration
+    pub fn get_investor_country(env: Env, wallet: Address) -> Option<String> {
+        let investor = Self::resolve_investor(env.clone(), wallet.clone());
+
+        if let Some(identity_storage) = env.storage().instance().get::<_, Address>(&IDENTITY_STORAGE) {
+            let disclosed: Option<Bytes> = env.invoke_contract(
+                &identity_storage,
+                &Symbol::new(&env, "get_disclosed_attribute"),
+                vec![&env, investor.into_val(&env), RESIDENCY_TOPIC.into_val(&env), Symbol::new(&env, "country").into_val(&env)],
+            );
+            if let Some(country) = disclosed.filter(|country| country.len() == 2) {
+                let mut code = [0u8; 2];
+                country.copy_into_slice(&mut code);
+                return Some(String::from_bytes(&env, &code));
+            }
+        }
+
+        Self::get_identity(env, wallet).map(|identity| identity.country)
+    }
+
+    // A wallet is verified when it is registered and, for every topic in the
+    // ClaimTopicsRegistry, holds valid claims from as many distinct issuers
+    // trusted for that topic as the topic's config requires
+    pub fn is_verified(env: Env, wallet: Address) -> bool {
+        let claim_topics_registry: Address = env.storage().instance().get(&CLAIM_TOPICS_REGISTRY).unwrap();
+        let required_topics: Vec<u32> = env.invoke_contract(
+            &claim_topics_registry,
+            &Symbol::new(&env, "list_claim_topics"),
+            Vec::new(&env),
+        );
+
+        Self::is_verified_for_topics(env, wallet, required_topics)
+    }
+
+    // Same as is_verified against a caller-supplied topic set, e.g. a token's
+    // required topics
+    pub fn is_verified_for_topics(env: Env, wallet: Address, topics: Vec<u32>) -> bool {
+        if !Self::is_registered(env.clone(), wallet.clone()) {
+            return false;
+        }
+
+        // Claims are held by the identity's primary wallet
+        let wallet = Self::resolve_investor(env.clone(), wallet);
+
+        for topic_id in topics.iter() {
+            if !Self::has_valid_trusted_claim(&env, &wallet, topic_id) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn set_identity_storage(env: Env, identity_storage: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&IDENTITY_STORAGE, &identity_storage);
+    }
+
+    pub fn set_claim_topics_registry(env: Env, claim_topics_registry: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&CLAIM_TOPICS_REGISTRY, &claim_topics_registry);
+    }
+
+    pub fn set_trusted_issuers_registry(env: Env, trusted_issuers_registry: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&TRUSTED_ISSUERS_REGISTRY, &trusted_issuers_registry);
+    }
+
+    pub fn get_identity_storage(env: Env) -> Address {
+        env.storage().instance().get(&IDENTITY_STORAGE).unwrap()
+    }
+
+    fn register_identity_internal(env: &Env, wallet: &Address, identity_id: &BytesN<32>, country: &String) {
+        Self::validate_country(country);
+
+        let key = (IDENTITIES, wallet.clone());
+        if env.storage().persistent().has(&key) || env.storage().persistent().has(&(LINKED_TO, wallet.clone())) {
+            panic!("Identity already registered");
+        }
+
+        let identity = Identity {
+            holder: wallet.clone(),
+            identity_id: identity_id.clone(),
+            country: country.clone(),
+            registered_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&key, &identity);
+
+        env.events().publish(
+            (symbol_short!("ID_REG"),),
+            (wallet.clone(), identity_id.clone(), country.clone())
+        );
+    }
+
+    fn has_valid_trusted_claim(env: &Env, subject: &Address, topic_id: u32) -> bool {
+        let identity_storage: Address = env.storage().instance().get(&IDENTITY_STORAGE).unwrap();
+        let claim_topics_registry: Address = env.storage().instance().get(&CLAIM_TOPICS_REGISTRY).unwrap();
+
+        let config: Option<TopicConfig> = env.invoke_contract(
+            &claim_topics_registry,
+            &Symbol::new(env, "get_topic_config"),
+            vec![env, topic_id.into_val(env)],
+        );
+        let required_issuers = config.map_or(1, |config| config.required_issuers.max(1));
+
+        let issuers: Vec<Address> = env.invoke_contract(
+            &identity_storage,
+            &Symbol::new(env, "get_valid_issuers"),
+            vec![env, subject.into_val(env), topic_id.into_val(env)],
+        );
+
+        // Claims are stored per issuer and only trusted issuers' valid claims
+        // are returned, so each issuer counts once
+        issuers.len() >= required_issuers
+    }
+
+    fn validate_country(country: &String) {
+        if country.len() != 2 {
+            panic!("Country must be an ISO 3166 alpha-2 code");
+        }
+    }
+
+    fn require_admin(env: &Env) {
+        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        admin.require_auth();
+    }
+}
@@ -1,10 +1,33 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, Symbol, Vec
+    contract, contractimpl, contracttype, symbol_short, vec, xdr::ToXdr, Address, Bytes, BytesN, Env,
+    IntoVal, String, Symbol, Vec
 };
 
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const CLAIMS: Symbol = symbol_short!("CLAIMS");
+const SUBJECT_INDEX: Symbol = symbol_short!("SUBJ_IDX");
+const ISSUER_INDEX: Symbol = symbol_short!("ISS_IDX");
+const ISSUERS_REGISTRY: Symbol = symbol_short!("ISS_REG");
+const TOPICS_REGISTRY: Symbol = symbol_short!("TOPIC_REG");
+const CLAIM_ISSUERS: Symbol = symbol_short!("CLM_ISS");
+const USED_SIGNATURES: Symbol = symbol_short!("USED_SIG");
+const STATUS_LIST: Symbol = symbol_short!("STATUS");
+const STATUS_NEXT: Symbol = symbol_short!("STATUS_NX");
+const DISCLOSED: Symbol = symbol_short!("DISCLOSED");
+const CLAIM_HISTORY: Symbol = symbol_short!("CLM_HIST");
+const CLAIM_PHASE: Symbol = symbol_short!("CLM_PHASE");
+const SIGNING_KEYS: Symbol = symbol_short!("SIGN_KEY");
+
+// Domain prefixes for selective-disclosure Merkle trees
+const MERKLE_LEAF: u8 = 0x00;
+const MERKLE_NODE: u8 = 0x01;
+
+// Each issuer's status list is stored in 256-bit chunks
+const STATUS_CHUNK_BITS: u32 = 256;
+
+// Domain tag for issuer-signed claims relayed by the admin
+const CLAIM_DOMAIN: &[u8] = b"SRWA_CLAIM_V1";
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
@@ -14,8 +37,44 @@ pub struct Claim {
     pub data_hash: BytesN<32>,
     pub issued_at: u64,
     pub valid_until: u64,
+    // Past valid_until the claim still verifies until grace_until, giving
+    // the investor time to renew
+    pub grace_until: u64,
     pub revoked: bool,
     pub revocation_ref: BytesN<32>,
+    // Position in the issuer's revocation status list
+    pub status_index: u32,
+}
+
+// Mirrors ClaimTopicsRegistry's TopicConfig
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TopicConfig {
+    pub name: String,
+    pub schema_hash: BytesN<32>,
+    pub default_validity: u64,
+    pub max_validity: u64,
+    pub required_issuers: u32,
+    pub grace_period: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ClaimStatus {
+    Valid,
+    Expired,
+    Revoked,
+    Grace,
+}
+
+// Claim as returned by the enumeration views, with its current status
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ClaimRecord {
+    pub subject: Address,
+    pub topic_id: u32,
+    pub claim: Claim,
+    pub status: ClaimStatus,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -23,7 +82,17 @@ pub struct Claim {
 pub struct Identity {
     pub holder: Address,
     pub identity_id: BytesN<32>,
-    pub verified: bool,
+    pub country: String, // ISO 3166 alpha-2 investor country
+    pub registered_at: u64,
+}
+
+// An attribute revealed on-chain by the subject of a Merkle claim
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisclosedAttribute {
+    pub issuer: Address,
+    pub value: Bytes,
+    pub disclosed_at: u64,
 }
 
 #[contract]
@@ -38,6 +107,7 @@ impl IdentityRegistryStorage {
         env.storage().instance().set(&ADMIN, &admin);
     }
 
+    // Trusted issuers submit their own claims
     pub fn add_claim(
         env: Env,
         subject: Address,
@@ -45,69 +115,451 @@ impl IdentityRegistryStorage {
         issuer: Address,
         data: Bytes,
         valid_until: u64,
+    ) {
+        issuer.require_auth();
+        Self::require_trusted_issuer(&env, &issuer, topic_id);
+        let (valid_until, grace_until) = Self::resolve_validity(&env, topic_id, valid_until);
+
+        let data_hash: BytesN<32> = env.crypto().sha256(&data).into();
+        Self::store_claim(&env, &subject, topic_id, &issuer, &data_hash, valid_until, grace_until);
+    }
+
+    // Admin relays a claim carrying a signature by the issuer's registered
+    // signing key over claim_digest(subject, topic_id, sha256(data), valid_until)
+    pub fn add_claim_signed(
+        env: Env,
+        subject: Address,
+        topic_id: u32,
+        issuer: Address,
+        data: Bytes,
+        valid_until: u64,
+        signature: BytesN<64>,
     ) {
         Self::require_admin(&env);
+        Self::require_trusted_issuer(&env, &issuer, topic_id);
 
-        let data_hash = env.crypto().sha256(&data);
-        let claim = Claim {
-            issuer: issuer.clone(),
-            topic_id,
-            data_hash: data_hash.into(),
-            issued_at: env.ledger().timestamp(),
-            valid_until,
-            revoked: false,
-            revocation_ref: BytesN::from_array(&env, &[0; 32]),
-        };
+        let data_hash: BytesN<32> = env.crypto().sha256(&data).into();
+        let digest = Self::claim_digest(env.clone(), subject.clone(), topic_id, data_hash.clone(), valid_until);
 
-        let key = (CLAIMS, subject.clone(), topic_id);
-        env.storage().persistent().set(&key, &claim);
+        // A relayed signature is single use, otherwise a revoked claim could be resurrected
+        let used_key = (USED_SIGNATURES, digest.clone());
+        if env.storage().persistent().has(&used_key) {
+            panic!("Signature already used");
+        }
+
+        let public_key = Self::get_claim_signing_key(env.clone(), issuer.clone())
+            .expect("Issuer signing key not registered");
+        env.crypto().ed25519_verify(&public_key, &Bytes::from_array(&env, &digest.to_array()), &signature);
+        env.storage().persistent().set(&used_key, &true);
+
+        let (valid_until, grace_until) = Self::resolve_validity(&env, topic_id, valid_until);
+        Self::store_claim(&env, &subject, topic_id, &issuer, &data_hash, valid_until, grace_until);
+    }
+
+    // The issuer authorizes, through its own account rules, the ed25519 key
+    // whose signatures the admin may relay. None revokes it.
+    pub fn set_claim_signing_key(env: Env, issuer: Address, public_key: Option<BytesN<32>>) {
+        issuer.require_auth();
+        let key = (SIGNING_KEYS, issuer.clone());
+        match &public_key {
+            Some(public_key) => env.storage().persistent().set(&key, public_key),
+            None => env.storage().persistent().remove(&key),
+        }
 
         env.events().publish(
-            (symbol_short!("CLAIM_ADD"),),
-            (subject, topic_id, issuer.clone())
+            (symbol_short!("SIGN_KEY"),),
+            (issuer, public_key)
         );
     }
 
+    pub fn get_claim_signing_key(env: Env, issuer: Address) -> Option<BytesN<32>> {
+        let key = (SIGNING_KEYS, issuer);
+        env.storage().persistent().get(&key)
+    }
+
+    // Selective-disclosure claim: data_hash holds the Merkle root over the
+    // claim's attribute leaves (see attribute_leaf) instead of sha256(data)
+    pub fn add_merkle_claim(
+        env: Env,
+        subject: Address,
+        topic_id: u32,
+        issuer: Address,
+        attributes_root: BytesN<32>,
+        valid_until: u64,
+    ) {
+        issuer.require_auth();
+        Self::require_trusted_issuer(&env, &issuer, topic_id);
+        let (valid_until, grace_until) = Self::resolve_validity(&env, topic_id, valid_until);
+
+        Self::store_claim(&env, &subject, topic_id, &issuer, &attributes_root, valid_until, grace_until);
+    }
+
+    // leaf = sha256(0x00 || salt || key xdr || value); the salt keeps
+    // low-entropy values such as country codes from being brute-forced
+    pub fn attribute_leaf(env: Env, key: Symbol, value: Bytes, salt: BytesN<32>) -> BytesN<32> {
+        let mut payload = Bytes::from_array(&env, &[MERKLE_LEAF]);
+        payload.append(&salt.into());
+        payload.append(&key.to_xdr(&env));
+        payload.append(&value);
+
+        env.crypto().sha256(&payload).into()
+    }
+
+    // True if a currently valid claim on the topic commits to key == value.
+    // Proof nodes are hashed as sorted pairs, so no path bits are needed
+    pub fn verify_attribute(
+        env: Env,
+        subject: Address,
+        topic_id: u32,
+        key: Symbol,
+        value: Bytes,
+        salt: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+    ) -> bool {
+        Self::find_attribute_issuer(&env, &subject, topic_id, key, value, salt, proof).is_some()
+    }
+
+    // The subject publishes one verified attribute, e.g. residency country,
+    // for contracts that cannot be handed a proof
+    pub fn disclose_attribute(
+        env: Env,
+        subject: Address,
+        topic_id: u32,
+        key: Symbol,
+        value: Bytes,
+        salt: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+    ) {
+        subject.require_auth();
+
+        let issuer = Self::find_attribute_issuer(&env, &subject, topic_id, key.clone(), value.clone(), salt, proof)
+            .unwrap_or_else(|| panic!("Invalid attribute proof"));
+
+        let disclosed = DisclosedAttribute {
+            issuer,
+            value,
+            disclosed_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&(DISCLOSED, subject.clone(), topic_id, key.clone()), &disclosed);
+
+        env.events().publish(
+            (symbol_short!("ATTR_DISC"),),
+            (subject, topic_id, key)
+        );
+    }
+
+    // Only returned while the claim it was proven against is still valid
+    pub fn get_disclosed_attribute(env: Env, subject: Address, topic_id: u32, key: Symbol) -> Option<Bytes> {
+        let disclosed: DisclosedAttribute = env.storage().persistent()
+            .get(&(DISCLOSED, subject.clone(), topic_id, key))?;
+
+        let claim = Self::get_claim_by_issuer(env.clone(), subject, topic_id, disclosed.issuer)?;
+        if Self::is_claim_verified(&env, &claim) && claim.issued_at <= disclosed.disclosed_at {
+            Some(disclosed.value)
+        } else {
+            None
+        }
+    }
+
+    // sha256(domain || network_id || storage contract || subject || topic_id || data_hash || valid_until)
+    pub fn claim_digest(
+        env: Env,
+        subject: Address,
+        topic_id: u32,
+        data_hash: BytesN<32>,
+        valid_until: u64,
+    ) -> BytesN<32> {
+        let mut payload = Bytes::from_slice(&env, CLAIM_DOMAIN);
+        payload.append(&env.ledger().network_id().into());
+        payload.append(&env.current_contract_address().to_xdr(&env));
+        payload.append(&subject.to_xdr(&env));
+        payload.extend_from_array(&topic_id.to_be_bytes());
+        payload.append(&data_hash.into());
+        payload.extend_from_array(&valid_until.to_be_bytes());
+
+        env.crypto().sha256(&payload).into()
+    }
+
+    // Issuers revoke their own claims; other issuers cannot touch them
     pub fn revoke_claim(
         env: Env,
         subject: Address,
         topic_id: u32,
+        issuer: Address,
+        revocation_ref: BytesN<32>,
+    ) {
+        issuer.require_auth();
+        Self::revoke(&env, &subject, topic_id, &issuer, &revocation_ref);
+    }
+
+    // Registry admin override, e.g. for a compromised or defunct issuer
+    pub fn admin_revoke_claim(
+        env: Env,
+        subject: Address,
+        topic_id: u32,
+        issuer: Address,
         revocation_ref: BytesN<32>,
     ) {
         Self::require_admin(&env);
+        Self::revoke(&env, &subject, topic_id, &issuer, &revocation_ref);
+    }
 
-        let key = (CLAIMS, subject.clone(), topic_id);
-        if let Some(mut claim) = env.storage().persistent().get::<_, Claim>(&key) {
-            claim.revoked = true;
-            claim.revocation_ref = revocation_ref.clone();
-            env.storage().persistent().set(&key, &claim);
+    // Bulk revocation against the issuer's status list; every claim whose
+    // status_index is set fails verification
+    pub fn revoke_status_indices(env: Env, issuer: Address, indices: Vec<u32>) {
+        issuer.require_auth();
+        Self::set_status_bits(&env, &issuer, &indices, true);
 
-            env.events().publish(
-                (symbol_short!("CLAIM_REV"),),
-                (subject, topic_id, revocation_ref.clone())
-            );
+        env.events().publish(
+            (symbol_short!("STAT_REV"),),
+            (issuer, indices)
+        );
+    }
+
+    pub fn reinstate_status_indices(env: Env, issuer: Address, indices: Vec<u32>) {
+        issuer.require_auth();
+        Self::set_status_bits(&env, &issuer, &indices, false);
+
+        env.events().publish(
+            (symbol_short!("STAT_RST"),),
+            (issuer, indices)
+        );
+    }
+
+    // Replaces a whole 256-bit chunk, for syncing from an off-chain status list
+    pub fn set_status_chunk(env: Env, issuer: Address, chunk_index: u32, bits: BytesN<32>) {
+        issuer.require_auth();
+
+        let allocated = Self::get_status_list_size(env.clone(), issuer.clone());
+        if chunk_index.saturating_mul(STATUS_CHUNK_BITS) >= allocated {
+            panic!("Status index not allocated");
         }
+
+        let key = (STATUS_LIST, issuer.clone(), chunk_index);
+        env.storage().persistent().set(&key, &bits);
+
+        env.events().publish(
+            (symbol_short!("STAT_SET"),),
+            (issuer, chunk_index)
+        );
+    }
+
+    pub fn get_status_chunk(env: Env, issuer: Address, chunk_index: u32) -> BytesN<32> {
+        let key = (STATUS_LIST, issuer, chunk_index);
+        env.storage().persistent().get(&key).unwrap_or_else(|| BytesN::from_array(&env, &[0; 32]))
     }
 
+    pub fn is_status_revoked(env: Env, issuer: Address, status_index: u32) -> bool {
+        Self::status_bit(&env, &issuer, status_index)
+    }
+
+    // Number of status indices allocated to the issuer's claims so far
+    pub fn get_status_list_size(env: Env, issuer: Address) -> u32 {
+        let key = (STATUS_NEXT, issuer);
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    pub fn set_issuers_registry(env: Env, trusted_issuers_registry: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&ISSUERS_REGISTRY, &trusted_issuers_registry);
+    }
+
+    // Claims are validated against the topic's validity bounds once set
+    pub fn set_topics_registry(env: Env, claim_topics_registry: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&TOPICS_REGISTRY, &claim_topics_registry);
+    }
+
+    // A topic can hold claims from several issuers; this returns a currently
+    // valid one if any, otherwise the most recently issued
     pub fn get_claim(env: Env, subject: Address, topic_id: u32) -> Option<Claim> {
-        let key = (CLAIMS, subject, topic_id);
+        let claims = Self::get_topic_claims(env.clone(), subject, topic_id);
+
+        let mut latest: Option<Claim> = None;
+        for claim in claims.iter() {
+            if Self::is_claim_valid(&env, &claim) {
+                return Some(claim);
+            }
+            if latest.as_ref().is_none_or(|current| claim.issued_at >= current.issued_at) {
+                latest = Some(claim);
+            }
+        }
+        latest
+    }
+
+    pub fn get_claim_by_issuer(env: Env, subject: Address, topic_id: u32, issuer: Address) -> Option<Claim> {
+        let key = (CLAIMS, subject, topic_id, issuer);
         env.storage().persistent().get(&key)
     }
 
+    pub fn get_topic_claims(env: Env, subject: Address, topic_id: u32) -> Vec<Claim> {
+        let mut claims = Vec::new(&env);
+        for issuer in Self::claim_issuers(&env, &subject, topic_id).iter() {
+            let key = (CLAIMS, subject.clone(), topic_id, issuer);
+            if let Some(claim) = env.storage().persistent().get::<_, Claim>(&key) {
+                claims.push_back(claim);
+            }
+        }
+        claims
+    }
+
+    // Currently trusted issuers holding a valid claim on the topic, used for
+    // k-of-n verification
+    pub fn get_valid_issuers(env: Env, subject: Address, topic_id: u32) -> Vec<Address> {
+        let mut issuers = Vec::new(&env);
+        for claim in Self::get_topic_claims(env.clone(), subject, topic_id).iter() {
+            if Self::is_claim_verified(&env, &claim) {
+                issuers.push_back(claim.issuer);
+            }
+        }
+        issuers
+    }
+
     pub fn has_claim(env: Env, subject: Address, topic_id: u32) -> bool {
-        let key = (CLAIMS, subject, topic_id);
-        if let Some(claim) = env.storage().persistent().get::<_, Claim>(&key) {
-            !claim.revoked && claim.valid_until > env.ledger().timestamp()
-        } else {
-            false
+        !Self::get_valid_issuers(env, subject, topic_id).is_empty()
+    }
+
+    // Every claim held by a subject, including revoked and expired ones
+    pub fn get_claims_by_subject(env: Env, subject: Address, start: u32, limit: u32) -> Vec<ClaimRecord> {
+        let entries = Self::subject_entries(&env, &subject);
+        let mut records = Vec::new(&env);
+
+        let end = start.saturating_add(limit).min(entries.len());
+        for i in start..end {
+            let (topic_id, issuer) = entries.get(i).unwrap();
+            if let Some(record) = Self::claim_record(&env, &subject, topic_id, &issuer) {
+                records.push_back(record);
+            }
         }
+
+        records
     }
 
-    pub fn get_claims_by_subject(env: Env, _subject: Address) -> Vec<(u32, Claim)> {
-        let claims = Vec::new(&env);
-        // Note: In a real implementation, you'd need an index to efficiently query by subject
-        // For now, this is a simplified version
-        claims
+    pub fn get_claim_count_by_subject(env: Env, subject: Address) -> u32 {
+        Self::subject_entries(&env, &subject).len()
+    }
+
+    // Every claim an issuer has produced
+    pub fn get_claims_by_issuer(env: Env, issuer: Address, start: u32, limit: u32) -> Vec<ClaimRecord> {
+        let entries = Self::issuer_entries(&env, &issuer);
+        let mut records = Vec::new(&env);
+
+        let end = start.saturating_add(limit).min(entries.len());
+        for i in start..end {
+            let (subject, topic_id) = entries.get(i).unwrap();
+            if let Some(record) = Self::claim_record(&env, &subject, topic_id, &issuer) {
+                records.push_back(record);
+            }
+        }
+
+        records
+    }
+
+    pub fn get_claim_count_by_issuer(env: Env, issuer: Address) -> u32 {
+        Self::issuer_entries(&env, &issuer).len()
+    }
+
+    // Extends a claim's validity in place, keeping its original issuance time;
+    // the replaced version is kept in the claim's history
+    pub fn renew_claim(env: Env, subject: Address, topic_id: u32, issuer: Address, valid_until: u64) {
+        issuer.require_auth();
+        Self::require_trusted_issuer(&env, &issuer, topic_id);
+
+        let key = (CLAIMS, subject.clone(), topic_id, issuer.clone());
+        let mut claim: Claim = env.storage().persistent().get(&key)
+            .unwrap_or_else(|| panic!("Claim not found"));
+        if Self::is_claim_revoked(&env, &claim) {
+            panic!("Claim is revoked");
+        }
+
+        let (valid_until, grace_until) = Self::resolve_validity(&env, topic_id, valid_until);
+        if valid_until <= claim.valid_until {
+            panic!("Renewal must extend validity");
+        }
+
+        let history_key = (CLAIM_HISTORY, subject.clone(), topic_id, issuer.clone());
+        let mut history: Vec<Claim> = env.storage().persistent().get(&history_key).unwrap_or_else(|| Vec::new(&env));
+        history.push_back(claim.clone());
+        env.storage().persistent().set(&history_key, &history);
+
+        claim.valid_until = valid_until;
+        claim.grace_until = grace_until;
+        env.storage().persistent().set(&key, &claim);
+        env.storage().persistent().remove(&(CLAIM_PHASE, subject.clone(), topic_id, issuer.clone()));
+
+        env.events().publish(
+            (symbol_short!("CLAIM_RNW"),),
+            (subject, topic_id, issuer, valid_until)
+        );
+    }
+
+    pub fn get_claim_history(env: Env, subject: Address, topic_id: u32, issuer: Address) -> Vec<Claim> {
+        let key = (CLAIM_HISTORY, subject, topic_id, issuer);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // Unrevoked claims of the subject expiring within `within` seconds,
+    // including those already in their grace period
+    pub fn get_expiring_claims(env: Env, subject: Address, within: u64) -> Vec<ClaimRecord> {
+        let mut records = Vec::new(&env);
+        for (topic_id, issuer) in Self::subject_entries(&env, &subject).iter() {
+            if let Some(record) = Self::claim_record(&env, &subject, topic_id, &issuer) {
+                if Self::is_expiring(&env, &record, within) {
+                    records.push_back(record);
+                }
+            }
+        }
+        records
+    }
+
+    // Same as get_expiring_claims across an issuer's claims, paginated over
+    // the issuer's claim index
+    pub fn get_expiring_claims_by_issuer(env: Env, issuer: Address, within: u64, start: u32, limit: u32) -> Vec<ClaimRecord> {
+        let entries = Self::issuer_entries(&env, &issuer);
+        let mut records = Vec::new(&env);
+
+        let end = start.saturating_add(limit).min(entries.len());
+        for i in start..end {
+            let (subject, topic_id) = entries.get(i).unwrap();
+            if let Some(record) = Self::claim_record(&env, &subject, topic_id, &issuer) {
+                if Self::is_expiring(&env, &record, within) {
+                    records.push_back(record);
+                }
+            }
+        }
+
+        records
+    }
+
+    // Permissionless: emits CLM_GRACE / CLM_EXP once per claim as it moves
+    // into its grace period or expires, for off-chain notifiers
+    pub fn sync_claim_status(env: Env, subject: Address) {
+        for (topic_id, issuer) in Self::subject_entries(&env, &subject).iter() {
+            let key = (CLAIMS, subject.clone(), topic_id, issuer.clone());
+            let claim: Claim = match env.storage().persistent().get(&key) {
+                Some(claim) => claim,
+                None => continue,
+            };
+
+            let phase: u32 = match Self::claim_status(&env, &claim) {
+                ClaimStatus::Grace => 1,
+                ClaimStatus::Expired => 2,
+                _ => continue,
+            };
+
+            let phase_key = (CLAIM_PHASE, subject.clone(), topic_id, issuer.clone());
+            let recorded: u32 = env.storage().persistent().get(&phase_key).unwrap_or(0);
+            if phase <= recorded {
+                continue;
+            }
+            env.storage().persistent().set(&phase_key, &phase);
+
+            let topic = if phase == 1 { symbol_short!("CLM_GRACE") } else { symbol_short!("CLM_EXP") };
+            env.events().publish(
+                (topic,),
+                (subject.clone(), topic_id, issuer, claim.valid_until, claim.grace_until)
+            );
+        }
     }
 
     pub fn set_admin(env: Env, new_admin: Address) {
@@ -119,12 +571,272 @@ impl IdentityRegistryStorage {
         env.storage().instance().get(&ADMIN).unwrap()
     }
 
+    fn revoke(env: &Env, subject: &Address, topic_id: u32, issuer: &Address, revocation_ref: &BytesN<32>) {
+        let key = (CLAIMS, subject.clone(), topic_id, issuer.clone());
+        let mut claim: Claim = env.storage().persistent().get(&key)
+            .unwrap_or_else(|| panic!("Claim not found"));
+        claim.revoked = true;
+        claim.revocation_ref = revocation_ref.clone();
+        env.storage().persistent().set(&key, &claim);
+
+        env.events().publish(
+            (symbol_short!("CLAIM_REV"),),
+            (subject.clone(), topic_id, issuer.clone(), revocation_ref.clone())
+        );
+    }
+
+    fn store_claim(
+        env: &Env,
+        subject: &Address,
+        topic_id: u32,
+        issuer: &Address,
+        data_hash: &BytesN<32>,
+        valid_until: u64,
+        grace_until: u64,
+    ) {
+        let claim = Claim {
+            issuer: issuer.clone(),
+            topic_id,
+            data_hash: data_hash.clone(),
+            issued_at: env.ledger().timestamp(),
+            valid_until,
+            grace_until,
+            revoked: false,
+            revocation_ref: BytesN::from_array(env, &[0; 32]),
+            status_index: Self::allocate_status_index(env, issuer),
+        };
+
+        let key = (CLAIMS, subject.clone(), topic_id, issuer.clone());
+        env.storage().persistent().set(&key, &claim);
+        env.storage().persistent().remove(&(CLAIM_PHASE, subject.clone(), topic_id, issuer.clone()));
+        Self::index_claim(env, subject, topic_id, issuer);
+
+        env.events().publish(
+            (symbol_short!("CLAIM_ADD"),),
+            (subject.clone(), topic_id, issuer.clone())
+        );
+    }
+
+    // valid_until == 0 takes the topic's default validity; anything beyond
+    // the topic's maximum validity is rejected. Returns (valid_until, grace_until)
+    fn resolve_validity(env: &Env, topic_id: u32, valid_until: u64) -> (u64, u64) {
+        let registry: Option<Address> = env.storage().instance().get(&TOPICS_REGISTRY);
+        let config: Option<TopicConfig> = match registry {
+            Some(registry) => env.invoke_contract(
+                &registry,
+                &Symbol::new(env, "get_topic_config"),
+                vec![env, topic_id.into_val(env)],
+            ),
+            None => None,
+        };
+
+        let config = match config {
+            Some(config) => config,
+            None => return (valid_until, valid_until),
+        };
+
+        let now = env.ledger().timestamp();
+        let valid_until = if valid_until == 0 && config.default_validity > 0 {
+            now + config.default_validity
+        } else {
+            valid_until
+        };
+
+        if config.max_validity > 0 && valid_until > now + config.max_validity {
+            panic!("Validity exceeds topic maximum");
+        }
+
+        (valid_until, valid_until.saturating_add(config.grace_period))
+    }
+
+    fn is_claim_valid(env: &Env, claim: &Claim) -> bool {
+        !Self::is_claim_revoked(env, claim) && claim.grace_until > env.ledger().timestamp()
+    }
+
+    // Valid and from an issuer that is still trusted for the topic, i.e. not
+    // removed, suspended or outside its active window
+    fn is_claim_verified(env: &Env, claim: &Claim) -> bool {
+        Self::is_claim_valid(env, claim) && Self::is_trusted_issuer(env, &claim.issuer, claim.topic_id)
+    }
+
+    fn is_expiring(env: &Env, record: &ClaimRecord, within: u64) -> bool {
+        match record.status {
+            ClaimStatus::Grace => true,
+            ClaimStatus::Valid => record.claim.valid_until <= env.ledger().timestamp().saturating_add(within),
+            _ => false,
+        }
+    }
+
+    fn claim_status(env: &Env, claim: &Claim) -> ClaimStatus {
+        let now = env.ledger().timestamp();
+        if Self::is_claim_revoked(env, claim) {
+            ClaimStatus::Revoked
+        } else if claim.valid_until > now {
+            ClaimStatus::Valid
+        } else if claim.grace_until > now {
+            ClaimStatus::Grace
+        } else {
+            ClaimStatus::Expired
+        }
+    }
+
+    fn is_claim_revoked(env: &Env, claim: &Claim) -> bool {
+        claim.revoked || Self::status_bit(env, &claim.issuer, claim.status_index)
+    }
+
+    fn find_attribute_issuer(
+        env: &Env,
+        subject: &Address,
+        topic_id: u32,
+        key: Symbol,
+        value: Bytes,
+        salt: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+    ) -> Option<Address> {
+        let mut node = Self::attribute_leaf(env.clone(), key, value, salt);
+        for sibling in proof.iter() {
+            let (left, right) = if node.to_array() <= sibling.to_array() {
+                (node, sibling)
+            } else {
+                (sibling, node)
+            };
+
+            let mut payload = Bytes::from_array(env, &[MERKLE_NODE]);
+            payload.append(&left.into());
+            payload.append(&right.into());
+            node = env.crypto().sha256(&payload).into();
+        }
+
+        Self::get_topic_claims(env.clone(), subject.clone(), topic_id).iter()
+            .find(|claim| claim.data_hash == node && Self::is_claim_verified(env, claim))
+            .map(|claim| claim.issuer)
+    }
+
+    fn allocate_status_index(env: &Env, issuer: &Address) -> u32 {
+        let key = (STATUS_NEXT, issuer.clone());
+        let index: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(index + 1));
+        index
+    }
+
+    fn status_bit(env: &Env, issuer: &Address, index: u32) -> bool {
+        let chunk = Self::get_status_chunk(env.clone(), issuer.clone(), index / STATUS_CHUNK_BITS).to_array();
+        let offset = index % STATUS_CHUNK_BITS;
+        chunk[(offset / 8) as usize] & (0x80 >> (offset % 8)) != 0
+    }
+
+    fn set_status_bits(env: &Env, issuer: &Address, indices: &Vec<u32>, revoked: bool) {
+        let allocated = Self::get_status_list_size(env.clone(), issuer.clone());
+
+        // Consecutive indices usually share a chunk, so only write on chunk change
+        let mut current: Option<(u32, [u8; 32])> = None;
+        for index in indices.iter() {
+            if index >= allocated {
+                panic!("Status index not allocated");
+            }
+
+            let chunk_index = index / STATUS_CHUNK_BITS;
+            if current.is_none_or(|(loaded, _)| loaded != chunk_index) {
+                if let Some((loaded, bits)) = current {
+                    let key = (STATUS_LIST, issuer.clone(), loaded);
+                    env.storage().persistent().set(&key, &BytesN::from_array(env, &bits));
+                }
+                let bits = Self::get_status_chunk(env.clone(), issuer.clone(), chunk_index).to_array();
+                current = Some((chunk_index, bits));
+            }
+
+            if let Some((_, ref mut bits)) = current {
+                let offset = index % STATUS_CHUNK_BITS;
+                let mask = 0x80u8 >> (offset % 8);
+                if revoked {
+                    bits[(offset / 8) as usize] |= mask;
+                } else {
+                    bits[(offset / 8) as usize] &= !mask;
+                }
+            }
+        }
+
+        if let Some((loaded, bits)) = current {
+            let key = (STATUS_LIST, issuer.clone(), loaded);
+            env.storage().persistent().set(&key, &BytesN::from_array(env, &bits));
+        }
+    }
+
+    fn require_trusted_issuer(env: &Env, issuer: &Address, topic_id: u32) {
+        if !Self::is_trusted_issuer(env, issuer, topic_id) {
+            panic!("Issuer not trusted for topic");
+        }
+    }
+
+    fn is_trusted_issuer(env: &Env, issuer: &Address, topic_id: u32) -> bool {
+        let registry: Address = env.storage().instance()
+            .get(&ISSUERS_REGISTRY)
+            .expect("Trusted issuers registry not set");
+
+        env.invoke_contract(
+            &registry,
+            &Symbol::new(env, "is_trusted"),
+            vec![env, issuer.into_val(env), topic_id.into_val(env)],
+        )
+    }
+
+    fn claim_record(env: &Env, subject: &Address, topic_id: u32, issuer: &Address) -> Option<ClaimRecord> {
+        let key = (CLAIMS, subject.clone(), topic_id, issuer.clone());
+        env.storage().persistent().get::<_, Claim>(&key).map(|claim| {
+            let status = Self::claim_status(env, &claim);
+
+            ClaimRecord {
+                subject: subject.clone(),
+                topic_id,
+                claim,
+                status,
+            }
+        })
+    }
+
+    fn claim_issuers(env: &Env, subject: &Address, topic_id: u32) -> Vec<Address> {
+        let key = (CLAIM_ISSUERS, subject.clone(), topic_id);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn subject_entries(env: &Env, subject: &Address) -> Vec<(u32, Address)> {
+        let key = (SUBJECT_INDEX, subject.clone());
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn issuer_entries(env: &Env, issuer: &Address) -> Vec<(Address, u32)> {
+        let key = (ISSUER_INDEX, issuer.clone());
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn index_claim(env: &Env, subject: &Address, topic_id: u32, issuer: &Address) {
+        let mut issuers = Self::claim_issuers(env, subject, topic_id);
+        if !issuers.contains(issuer) {
+            issuers.push_back(issuer.clone());
+            env.storage().persistent().set(&(CLAIM_ISSUERS, subject.clone(), topic_id), &issuers);
+        }
+
+        let subject_entry = (topic_id, issuer.clone());
+        let mut subject_entries = Self::subject_entries(env, subject);
+        if !subject_entries.contains(&subject_entry) {
+            subject_entries.push_back(subject_entry);
+            env.storage().persistent().set(&(SUBJECT_INDEX, subject.clone()), &subject_entries);
+        }
+
+        let issuer_entry = (subject.clone(), topic_id);
+        let mut issuer_entries = Self::issuer_entries(env, issuer);
+        if !issuer_entries.contains(&issuer_entry) {
+            issuer_entries.push_back(issuer_entry);
+            env.storage().persistent().set(&(ISSUER_INDEX, issuer.clone()), &issuer_entries);
+        }
+    }
+
     fn require_admin(env: &Env) {
         let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
         admin.require_auth();
     }
 }
 
-// pub mod identity_registry;
+pub mod identity_registry;
 
 mod test;
\ No newline at end of file
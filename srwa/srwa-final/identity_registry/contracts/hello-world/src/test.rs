@@ -1,7 +1,58 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, BytesN, Bytes};
+use crate::identity_registry::{IdentityRegistry, IdentityRegistryClient};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+    vec,
+    Address, Env, BytesN, Bytes, IntoVal, String, Symbol, Vec,
+};
+
+#[contract]
+pub struct MockClaimTopicsRegistry;
+
+#[contractimpl]
+impl MockClaimTopicsRegistry {
+    pub fn list_claim_topics(env: Env) -> Vec<u32> {
+        vec![&env, 1u32]
+    }
+
+    pub fn configure(env: Env, topic_id: u32, default_validity: u64, max_validity: u64, required_issuers: u32, grace_period: u64) {
+        let config = TopicConfig {
+            name: String::from_str(&env, "KYC"),
+            schema_hash: BytesN::from_array(&env, &[0; 32]),
+            default_validity,
+            max_validity,
+            required_issuers,
+            grace_period,
+        };
+        env.storage().instance().set(&topic_id, &config);
+    }
+
+    pub fn get_topic_config(env: Env, topic_id: u32) -> Option<TopicConfig> {
+        env.storage().instance().get(&topic_id)
+    }
+}
+
+#[contract]
+pub struct MockTrustedIssuersRegistry;
+
+#[contractimpl]
+impl MockTrustedIssuersRegistry {
+    pub fn trust(env: Env, issuer: Address) {
+        env.storage().instance().set(&issuer, &true);
+    }
+
+    pub fn suspend(env: Env, issuer: Address) {
+        env.storage().instance().set(&issuer, &false);
+    }
+
+    pub fn is_trusted(env: Env, issuer: Address, _topic_id: u32) -> bool {
+        env.storage().instance().get(&issuer).unwrap_or(false)
+    }
+}
 
 #[test]
 fn test_identity_registry_storage() {
@@ -19,6 +70,10 @@ fn test_identity_registry_storage() {
     // Mock admin authorization
     env.mock_all_auths();
 
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&issuer);
+    client.set_issuers_registry(&issuers_id);
+
     // Add claim
     let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
     let topic_id = 1u32; // KYC
@@ -37,10 +92,584 @@ fn test_identity_registry_storage() {
 
     // Revoke claim
     let revocation_ref = BytesN::from_array(&env, &[5; 32]);
-    client.revoke_claim(&subject, &topic_id, &revocation_ref);
+    client.revoke_claim(&subject, &topic_id, &issuer, &revocation_ref);
 
     // Check claim is revoked
     assert!(!client.has_claim(&subject, &topic_id));
     let revoked_claim = client.get_claim(&subject, &topic_id).unwrap();
     assert!(revoked_claim.revoked);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_identity_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let kyc_provider = Address::generate(&env);
+    let unknown_provider = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let other_investor = Address::generate(&env);
+
+    let storage_id = env.register(IdentityRegistryStorage, ());
+    let storage = IdentityRegistryStorageClient::new(&env, &storage_id);
+    storage.initialize(&admin);
+
+    let topics_id = env.register(MockClaimTopicsRegistry, ());
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&kyc_provider);
+    storage.set_issuers_registry(&issuers_id);
+
+    let registry_id = env.register(IdentityRegistry, ());
+    let registry = IdentityRegistryClient::new(&env, &registry_id);
+    registry.init_identity_registry(&admin, &storage_id, &topics_id, &issuers_id);
+
+    // Batch registration
+    let de = String::from_str(&env, "DE");
+    let us = String::from_str(&env, "US");
+    registry.batch_register_identity(
+        &vec![&env, investor.clone(), other_investor.clone()],
+        &vec![&env, BytesN::from_array(&env, &[1; 32]), BytesN::from_array(&env, &[2; 32])],
+        &vec![&env, de.clone(), us.clone()],
+    );
+    assert!(registry.is_registered(&investor));
+    assert_eq!(registry.get_investor_country(&investor), Some(de));
+
+    // Registered but no KYC claim yet
+    assert!(!registry.is_verified(&investor));
+
+    let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
+    let valid_until = env.ledger().timestamp() + 86400;
+
+    // Issuers that are not trusted for the topic cannot add claims
+    assert!(storage.try_add_claim(&investor, &1u32, &unknown_provider, &data, &valid_until).is_err());
+    assert!(!registry.is_verified(&investor));
+
+    storage.add_claim(&investor, &1u32, &kyc_provider, &data, &valid_until);
+    assert!(registry.is_verified(&investor));
+
+    // Country updates and deletion
+    registry.update_country(&investor, &us);
+    assert_eq!(registry.get_investor_country(&investor), Some(us));
+
+    registry.delete_identity(&investor);
+    assert!(!registry.is_registered(&investor));
+    assert!(!registry.is_verified(&investor));
+}
+
+#[test]
+fn test_claim_enumeration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(IdentityRegistryStorage, ());
+    let client = IdentityRegistryStorageClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let subject = Address::generate(&env);
+    let other_subject = Address::generate(&env);
+    let kyc_provider = Address::generate(&env);
+    let aml_provider = Address::generate(&env);
+
+    client.initialize(&admin);
+
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    let issuers = MockTrustedIssuersRegistryClient::new(&env, &issuers_id);
+    issuers.trust(&kyc_provider);
+    issuers.trust(&aml_provider);
+    client.set_issuers_registry(&issuers_id);
+
+    let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
+    let now = env.ledger().timestamp();
+
+    client.add_claim(&subject, &1u32, &kyc_provider, &data, &(now + 86400));
+    client.add_claim(&subject, &2u32, &aml_provider, &data, &(now + 86400));
+    client.add_claim(&subject, &3u32, &kyc_provider, &data, &now); // already expired
+    client.add_claim(&other_subject, &1u32, &kyc_provider, &data, &(now + 86400));
+    client.revoke_claim(&subject, &2u32, &aml_provider, &BytesN::from_array(&env, &[9; 32]));
+
+    assert_eq!(client.get_claim_count_by_subject(&subject), 3);
+
+    let records = client.get_claims_by_subject(&subject, &0u32, &10u32);
+    assert_eq!(records.len(), 3);
+    assert_eq!(records.get(0).unwrap().status, ClaimStatus::Valid);
+    assert_eq!(records.get(1).unwrap().status, ClaimStatus::Revoked);
+    assert_eq!(records.get(2).unwrap().status, ClaimStatus::Expired);
+
+    // Pagination
+    let page = client.get_claims_by_subject(&subject, &1u32, &1u32);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().topic_id, 2u32);
+    assert_eq!(client.get_claims_by_subject(&subject, &5u32, &10u32).len(), 0);
+
+    // Issuer index
+    assert_eq!(client.get_claim_count_by_issuer(&kyc_provider), 3);
+    assert_eq!(client.get_claims_by_issuer(&aml_provider, &0u32, &10u32).len(), 1);
+
+    // A second issuer on the same topic is held alongside the first
+    client.add_claim(&other_subject, &1u32, &aml_provider, &data, &(now + 86400));
+    assert_eq!(client.get_claim_count_by_issuer(&kyc_provider), 3);
+    assert_eq!(client.get_claim_count_by_issuer(&aml_provider), 2);
+    assert_eq!(client.get_claim_count_by_subject(&other_subject), 2);
+}
+
+#[test]
+fn test_issuer_submitted_and_signed_claims() {
+    let env = Env::default();
+
+    let contract_id = env.register(IdentityRegistryStorage, ());
+    let client = IdentityRegistryStorageClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let subject = Address::generate(&env);
+
+    let kyc_provider = Address::generate(&env);
+    let other_provider = Address::generate(&env);
+    let signing_key = SigningKey::from_bytes(&[3u8; 32]);
+
+    client.initialize(&admin);
+
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&kyc_provider);
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&other_provider);
+    env.mock_all_auths();
+    client.set_issuers_registry(&issuers_id);
+
+    let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
+    let valid_until = env.ledger().timestamp() + 86400;
+
+    // The admin alone cannot add a claim on behalf of an issuer
+    env.mock_auths(&[MockAuth {
+        address: &admin,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "add_claim",
+            args: (&subject, 1u32, &kyc_provider, &data, valid_until).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_add_claim(&subject, &1u32, &kyc_provider, &data, &valid_until).is_err());
+
+    // Relayed claim carrying a signature by the issuer's registered key
+    env.mock_all_auths();
+    let data_hash: BytesN<32> = env.crypto().sha256(&data).into();
+    let digest = client.claim_digest(&subject, &2u32, &data_hash, &valid_until);
+    let signature = BytesN::from_array(&env, &signing_key.sign(&digest.to_array()).to_bytes());
+
+    assert!(client
+        .try_add_claim_signed(&subject, &2u32, &kyc_provider, &data, &valid_until, &signature)
+        .is_err());
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    client.set_claim_signing_key(&kyc_provider, &Some(public_key.clone()));
+    assert_eq!(client.get_claim_signing_key(&kyc_provider), Some(public_key));
+
+    client.add_claim_signed(&subject, &2u32, &kyc_provider, &data, &valid_until, &signature);
+    assert!(client.has_claim(&subject, &2u32));
+    assert_eq!(client.get_claim(&subject, &2u32).unwrap().issuer, kyc_provider);
+
+    // Signature cannot be replayed, and cannot be moved to other terms
+    assert!(client
+        .try_add_claim_signed(&subject, &2u32, &kyc_provider, &data, &valid_until, &signature)
+        .is_err());
+    assert!(client
+        .try_add_claim_signed(&subject, &3u32, &kyc_provider, &data, &valid_until, &signature)
+        .is_err());
+
+    // Another trusted issuer neither overwrites nor revokes the claim
+    let other_data = Bytes::from_array(&env, &[9, 9]);
+    client.add_claim(&subject, &2u32, &other_provider, &other_data, &valid_until);
+    let kyc_claim = client.get_claim_by_issuer(&subject, &2u32, &kyc_provider).unwrap();
+    assert_eq!(kyc_claim.data_hash, data_hash);
+
+    let revocation_ref = BytesN::from_array(&env, &[5; 32]);
+    env.mock_auths(&[MockAuth {
+        address: &other_provider,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "revoke_claim",
+            args: (&subject, 2u32, &kyc_provider, &revocation_ref).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_revoke_claim(&subject, &2u32, &kyc_provider, &revocation_ref).is_err());
+
+    // The registry admin can still revoke any claim
+    env.mock_all_auths();
+    client.admin_revoke_claim(&subject, &2u32, &kyc_provider, &revocation_ref);
+    let kyc_claim = client.get_claim_by_issuer(&subject, &2u32, &kyc_provider).unwrap();
+    assert!(kyc_claim.revoked);
+    assert!(!client.get_claim_by_issuer(&subject, &2u32, &other_provider).unwrap().revoked);
+}
+
+#[test]
+fn test_linked_wallets() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let kyc_provider = Address::generate(&env);
+    let custody = Address::generate(&env);
+    let trading = Address::generate(&env);
+    let collateral = Address::generate(&env);
+
+    let storage_id = env.register(IdentityRegistryStorage, ());
+    let storage = IdentityRegistryStorageClient::new(&env, &storage_id);
+    storage.initialize(&admin);
+
+    let topics_id = env.register(MockClaimTopicsRegistry, ());
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&kyc_provider);
+    storage.set_issuers_registry(&issuers_id);
+
+    let registry_id = env.register(IdentityRegistry, ());
+    let registry = IdentityRegistryClient::new(&env, &registry_id);
+    registry.init_identity_registry(&admin, &storage_id, &topics_id, &issuers_id);
+
+    let de = String::from_str(&env, "DE");
+    registry.register_identity(&custody, &BytesN::from_array(&env, &[1; 32]), &de);
+
+    let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
+    let valid_until = env.ledger().timestamp() + 86400;
+    storage.add_claim(&custody, &1u32, &kyc_provider, &data, &valid_until);
+
+    registry.link_wallet(&custody, &trading);
+    registry.link_wallet(&custody, &collateral);
+
+    // Linked wallets resolve to the identity and share its claims and country
+    assert_eq!(registry.resolve_investor(&trading), custody);
+    assert!(registry.is_registered(&collateral));
+    assert!(registry.is_verified(&trading));
+    assert_eq!(registry.get_investor_country(&collateral), Some(de));
+    assert_eq!(registry.get_linked_wallets(&trading).len(), 3);
+
+    // A wallet can only belong to one identity
+    assert!(registry.try_register_identity(&trading, &BytesN::from_array(&env, &[2; 32]), &String::from_str(&env, "US")).is_err());
+
+    registry.unlink_wallet(&custody, &trading);
+    assert_eq!(registry.resolve_investor(&trading), trading);
+    assert!(!registry.is_verified(&trading));
+    assert_eq!(registry.get_linked_wallets(&custody).len(), 2);
+}
+
+#[test]
+fn test_topic_validity_and_required_issuers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let first_provider = Address::generate(&env);
+    let second_provider = Address::generate(&env);
+
+    let storage_id = env.register(IdentityRegistryStorage, ());
+    let storage = IdentityRegistryStorageClient::new(&env, &storage_id);
+    storage.initialize(&admin);
+
+    let topics_id = env.register(MockClaimTopicsRegistry, ());
+    MockClaimTopicsRegistryClient::new(&env, &topics_id).configure(&1u32, &86400u64, &(86400u64 * 365), &2u32, &0u64);
+    storage.set_topics_registry(&topics_id);
+
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    let issuers = MockTrustedIssuersRegistryClient::new(&env, &issuers_id);
+    issuers.trust(&first_provider);
+    issuers.trust(&second_provider);
+    storage.set_issuers_registry(&issuers_id);
+
+    let registry_id = env.register(IdentityRegistry, ());
+    let registry = IdentityRegistryClient::new(&env, &registry_id);
+    registry.init_identity_registry(&admin, &storage_id, &topics_id, &issuers_id);
+    registry.register_identity(&investor, &BytesN::from_array(&env, &[1; 32]), &String::from_str(&env, "DE"));
+
+    let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
+    let now = env.ledger().timestamp();
+
+    // Validity beyond the topic maximum is rejected
+    assert!(storage.try_add_claim(&investor, &1u32, &first_provider, &data, &(now + 86400 * 366)).is_err());
+
+    // Zero validity takes the topic default
+    storage.add_claim(&investor, &1u32, &first_provider, &data, &0u64);
+    assert_eq!(storage.get_claim_by_issuer(&investor, &1u32, &first_provider).unwrap().valid_until, now + 86400);
+
+    // One issuer is not enough for a 2-of-n topic
+    assert!(!registry.is_verified(&investor));
+
+    storage.add_claim(&investor, &1u32, &second_provider, &data, &(now + 86400));
+    assert_eq!(storage.get_valid_issuers(&investor, &1u32).len(), 2);
+    assert!(registry.is_verified(&investor));
+
+    storage.revoke_claim(&investor, &1u32, &second_provider, &BytesN::from_array(&env, &[5; 32]));
+    assert!(storage.has_claim(&investor, &1u32));
+    assert!(!registry.is_verified(&investor));
+}
+
+#[test]
+fn test_verified_for_topics() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let kyc_provider = Address::generate(&env);
+
+    let storage_id = env.register(IdentityRegistryStorage, ());
+    let storage = IdentityRegistryStorageClient::new(&env, &storage_id);
+    storage.initialize(&admin);
+
+    let topics_id = env.register(MockClaimTopicsRegistry, ());
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&kyc_provider);
+    storage.set_issuers_registry(&issuers_id);
+
+    let registry_id = env.register(IdentityRegistry, ());
+    let registry = IdentityRegistryClient::new(&env, &registry_id);
+    registry.init_identity_registry(&admin, &storage_id, &topics_id, &issuers_id);
+    registry.register_identity(&investor, &BytesN::from_array(&env, &[1; 32]), &String::from_str(&env, "DE"));
+
+    let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
+    storage.add_claim(&investor, &101u32, &kyc_provider, &data, &(env.ledger().timestamp() + 86400));
+
+    // Holds the token-specific topic but not the registry-wide KYC topic
+    assert!(registry.is_verified_for_topics(&investor, &vec![&env, 101u32]));
+    assert!(!registry.is_verified_for_topics(&investor, &vec![&env, 1u32, 101u32]));
+    assert!(!registry.is_verified(&investor));
+}
+
+#[test]
+fn test_status_list_revocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let kyc_provider = Address::generate(&env);
+
+    let storage_id = env.register(IdentityRegistryStorage, ());
+    let storage = IdentityRegistryStorageClient::new(&env, &storage_id);
+    storage.initialize(&admin);
+
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&kyc_provider);
+    storage.set_issuers_registry(&issuers_id);
+
+    let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
+    let valid_until = env.ledger().timestamp() + 86400;
+
+    let mut subjects = Vec::new(&env);
+    for _ in 0..3 {
+        let subject = Address::generate(&env);
+        storage.add_claim(&subject, &1u32, &kyc_provider, &data, &valid_until);
+        subjects.push_back(subject);
+    }
+
+    // Claims get consecutive indices in the issuer's list
+    assert_eq!(storage.get_status_list_size(&kyc_provider), 3);
+    assert_eq!(storage.get_claim(&subjects.get(2).unwrap(), &1u32).unwrap().status_index, 2);
+
+    storage.revoke_status_indices(&kyc_provider, &vec![&env, 0u32, 2u32]);
+    assert!(!storage.has_claim(&subjects.get(0).unwrap(), &1u32));
+    assert!(storage.has_claim(&subjects.get(1).unwrap(), &1u32));
+    assert!(!storage.has_claim(&subjects.get(2).unwrap(), &1u32));
+    assert_eq!(
+        storage.get_claims_by_subject(&subjects.get(0).unwrap(), &0u32, &1u32).get(0).unwrap().status,
+        ClaimStatus::Revoked
+    );
+
+    let mut expected = [0u8; 32];
+    expected[0] = 0b1010_0000;
+    assert_eq!(storage.get_status_chunk(&kyc_provider, &0u32), BytesN::from_array(&env, &expected));
+
+    storage.reinstate_status_indices(&kyc_provider, &vec![&env, 2u32]);
+    assert!(storage.has_claim(&subjects.get(2).unwrap(), &1u32));
+
+    // Whole chunks can be replaced at once
+    storage.set_status_chunk(&kyc_provider, &0u32, &BytesN::from_array(&env, &[0xff; 32]));
+    assert!(!storage.has_claim(&subjects.get(1).unwrap(), &1u32));
+
+    // Indices past the allocated range are rejected
+    assert!(storage.try_revoke_status_indices(&kyc_provider, &vec![&env, 3u32]).is_err());
+    assert!(storage.try_set_status_chunk(&kyc_provider, &1u32, &BytesN::from_array(&env, &[0; 32])).is_err());
+}
+
+fn merkle_node(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (left, right) = if a.to_array() <= b.to_array() { (a, b) } else { (b, a) };
+    let mut payload = Bytes::from_array(env, &[1u8]);
+    payload.append(&left.clone().into());
+    payload.append(&right.clone().into());
+    env.crypto().sha256(&payload).into()
+}
+
+#[test]
+fn test_selective_disclosure_claims() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let kyc_provider = Address::generate(&env);
+
+    let storage_id = env.register(IdentityRegistryStorage, ());
+    let storage = IdentityRegistryStorageClient::new(&env, &storage_id);
+    storage.initialize(&admin);
+
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&kyc_provider);
+    storage.set_issuers_registry(&issuers_id);
+
+    // Three salted attributes: country, accreditation tier, date of birth
+    let country = Symbol::new(&env, "country");
+    let de = Bytes::from_slice(&env, b"DE");
+    let salts = [
+        BytesN::from_array(&env, &[1; 32]),
+        BytesN::from_array(&env, &[2; 32]),
+        BytesN::from_array(&env, &[3; 32]),
+    ];
+    let country_leaf = storage.attribute_leaf(&country, &de, &salts[0]);
+    let tier_leaf = storage.attribute_leaf(&Symbol::new(&env, "tier"), &Bytes::from_slice(&env, b"2"), &salts[1]);
+    let dob_leaf = storage.attribute_leaf(&Symbol::new(&env, "dob"), &Bytes::from_slice(&env, b"1980-01-01"), &salts[2]);
+    let root = merkle_node(&env, &merkle_node(&env, &country_leaf, &tier_leaf), &dob_leaf);
+
+    storage.add_merkle_claim(&investor, &4u32, &kyc_provider, &root, &(env.ledger().timestamp() + 86400));
+
+    let proof = vec![&env, tier_leaf.clone(), dob_leaf.clone()];
+    assert!(storage.verify_attribute(&investor, &4u32, &country, &de, &salts[0], &proof));
+    assert!(!storage.verify_attribute(&investor, &4u32, &country, &Bytes::from_slice(&env, b"US"), &salts[0], &proof));
+    assert!(!storage.verify_attribute(&investor, &4u32, &country, &de, &salts[1], &proof));
+
+    // Disclosure publishes only the proven attribute
+    assert!(storage.try_disclose_attribute(&investor, &4u32, &country, &Bytes::from_slice(&env, b"US"), &salts[0], &proof).is_err());
+    storage.disclose_attribute(&investor, &4u32, &country, &de, &salts[0], &proof);
+    assert_eq!(storage.get_disclosed_attribute(&investor, &4u32, &country), Some(de.clone()));
+    assert!(storage.get_disclosed_attribute(&investor, &4u32, &Symbol::new(&env, "dob")).is_none());
+
+    // Nothing verifies while the issuer is suspended
+    let issuers = MockTrustedIssuersRegistryClient::new(&env, &issuers_id);
+    issuers.suspend(&kyc_provider);
+    assert!(!storage.verify_attribute(&investor, &4u32, &country, &de, &salts[0], &proof));
+    assert!(storage.get_disclosed_attribute(&investor, &4u32, &country).is_none());
+    assert!(!storage.has_claim(&investor, &4u32));
+    issuers.trust(&kyc_provider);
+    assert_eq!(storage.get_disclosed_attribute(&investor, &4u32, &country), Some(de));
+
+    // Revoking the claim withdraws the disclosure
+    storage.revoke_claim(&investor, &4u32, &kyc_provider, &BytesN::from_array(&env, &[9; 32]));
+    assert!(storage.get_disclosed_attribute(&investor, &4u32, &country).is_none());
+}
+
+#[test]
+fn test_claim_expiry_and_renewal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let kyc_provider = Address::generate(&env);
+
+    let storage_id = env.register(IdentityRegistryStorage, ());
+    let storage = IdentityRegistryStorageClient::new(&env, &storage_id);
+    storage.initialize(&admin);
+
+    // KYC claims last 30 days with a 7 day grace period
+    let day = 86400u64;
+    let topics_id = env.register(MockClaimTopicsRegistry, ());
+    MockClaimTopicsRegistryClient::new(&env, &topics_id).configure(&1u32, &(30 * day), &(365 * day), &1u32, &(7 * day));
+    storage.set_topics_registry(&topics_id);
+
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&kyc_provider);
+    storage.set_issuers_registry(&issuers_id);
+
+    let data = Bytes::from_array(&env, &[1, 2, 3, 4]);
+    storage.add_claim(&investor, &1u32, &kyc_provider, &data, &0u64);
+
+    assert_eq!(storage.get_expiring_claims(&investor, &(7 * day)).len(), 0);
+    assert_eq!(storage.get_expiring_claims(&investor, &(30 * day)).len(), 1);
+    assert_eq!(storage.get_expiring_claims_by_issuer(&kyc_provider, &(30 * day), &0u32, &10u32).len(), 1);
+
+    // Into the grace period: still valid
+    env.ledger().with_mut(|li| li.timestamp = 32 * day);
+    assert!(storage.has_claim(&investor, &1u32));
+    assert_eq!(storage.get_claims_by_subject(&investor, &0u32, &1u32).get(0).unwrap().status, ClaimStatus::Grace);
+
+    storage.sync_claim_status(&investor);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                storage_id.clone(),
+                (symbol_short!("CLM_GRACE"),).into_val(&env),
+                (investor.clone(), 1u32, kyc_provider.clone(), 30 * day, 37 * day).into_val(&env),
+            ),
+        ]
+    );
+
+    // Past the grace period
+    env.ledger().with_mut(|li| li.timestamp = 40 * day);
+    assert!(!storage.has_claim(&investor, &1u32));
+    storage.sync_claim_status(&investor);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                storage_id.clone(),
+                (symbol_short!("CLM_EXP"),).into_val(&env),
+                (investor.clone(), 1u32, kyc_provider.clone(), 30 * day, 37 * day).into_val(&env),
+            ),
+        ]
+    );
+
+    // Renewal keeps the previous version in the claim history
+    assert!(storage.try_renew_claim(&investor, &1u32, &kyc_provider, &(30 * day)).is_err());
+    storage.renew_claim(&investor, &1u32, &kyc_provider, &0u64);
+    assert!(storage.has_claim(&investor, &1u32));
+
+    let renewed = storage.get_claim(&investor, &1u32).unwrap();
+    assert_eq!(renewed.valid_until, 70 * day);
+    assert_eq!(renewed.grace_until, 77 * day);
+    assert_eq!(renewed.issued_at, 0);
+
+    let history = storage.get_claim_history(&investor, &1u32, &kyc_provider);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().valid_until, 30 * day);
+}
+
+#[test]
+fn test_country_from_residency_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let kyc_provider = Address::generate(&env);
+
+    let storage_id = env.register(IdentityRegistryStorage, ());
+    let storage = IdentityRegistryStorageClient::new(&env, &storage_id);
+    storage.initialize(&admin);
+
+    let topics_id = env.register(MockClaimTopicsRegistry, ());
+    let issuers_id = env.register(MockTrustedIssuersRegistry, ());
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).trust(&kyc_provider);
+    storage.set_issuers_registry(&issuers_id);
+
+    let registry_id = env.register(IdentityRegistry, ());
+    let registry = IdentityRegistryClient::new(&env, &registry_id);
+    registry.init_identity_registry(&admin, &storage_id, &topics_id, &issuers_id);
+    registry.register_identity(&investor, &BytesN::from_array(&env, &[1; 32]), &String::from_str(&env, "DE"));
+
+    assert_eq!(registry.get_investor_country(&investor), Some(String::from_str(&env, "DE")));
+    assert!(registry.get_investor_country(&Address::generate(&env)).is_none());
+
+    // Single-attribute residency claim: the leaf is the root
+    let country = Symbol::new(&env, "country");
+    let fr = Bytes::from_slice(&env, b"FR");
+    let salt = BytesN::from_array(&env, &[4; 32]);
+    let root = storage.attribute_leaf(&country, &fr, &salt);
+    storage.add_merkle_claim(&investor, &4u32, &kyc_provider, &root, &(env.ledger().timestamp() + 86400));
+    storage.disclose_attribute(&investor, &4u32, &country, &fr, &salt, &Vec::new(&env));
+
+    assert_eq!(registry.get_investor_country(&investor), Some(String::from_str(&env, "FR")));
+
+    // A suspended issuer's residency claim no longer overrides the registered country
+    MockTrustedIssuersRegistryClient::new(&env, &issuers_id).suspend(&kyc_provider);
+    assert_eq!(registry.get_investor_country(&investor), Some(String::from_str(&env, "DE")));
+}
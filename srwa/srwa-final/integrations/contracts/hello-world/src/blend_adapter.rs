@@ -65,7 +65,7 @@ impl BlendAdapter {
         Self::require_admin(&env);
 
         let key = (AUTHORIZED_RESERVES, srwa_token.clone());
-        if let Some(reserve_info) = env.storage().persistent().get::<_, ReserveInfo>(&key) {
+        if env.storage().persistent().has(&key) {
             // Remove authorization from pool contract
             let pool_key = (POOL_CONTRACTS, srwa_token.clone());
             if let Some(pool_contract) = env.storage().persistent().get::<_, Address>(&pool_key) {
@@ -246,17 +246,17 @@ impl BlendAdapter {
 
     // Helper functions
 
-    fn get_oracle_price(env: &Env, oracle: &Address) -> i128 {
+    fn get_oracle_price(_env: &Env, _oracle: &Address) -> i128 {
         // This would call the oracle contract to get the current price
         // For now, return a mock price
         1_000_000 // $1.00 with 6 decimals
     }
 
     fn check_user_compliance(
-        env: &Env,
-        user: &Address,
-        srwa_token: &Address,
-        amount: i128,
+        _env: &Env,
+        _user: &Address,
+        _srwa_token: &Address,
+        _amount: i128,
     ) -> bool {
         // This would check if the user can transfer the SRWA tokens to the pool
         // by calling the compliance contract
@@ -264,12 +264,12 @@ impl BlendAdapter {
         true
     }
 
-    fn authorize_pool_in_token(env: &Env, token: &Address, pool: &Address) {
+    fn authorize_pool_in_token(_env: &Env, _token: &Address, _pool: &Address) {
         // This would call the SRWA token's set_authorized function
         // For now, simplified implementation
     }
 
-    fn deauthorize_pool_in_token(env: &Env, token: &Address, pool: &Address) {
+    fn deauthorize_pool_in_token(_env: &Env, _token: &Address, _pool: &Address) {
         // This would call the SRWA token's set_authorized function with false
         // For now, simplified implementation
     }
@@ -168,24 +168,24 @@ impl SoroSwapAdapter {
 
     // Helper functions
 
-    fn is_srwa_token(env: &Env, token: &Address) -> bool {
+    fn is_srwa_token(_env: &Env, _token: &Address) -> bool {
         // This would check if the token is an SRWA token by calling its interface
         // For now, simplified implementation
         true
     }
 
-    fn get_token_compliance(env: &Env, token: &Address) -> Option<Address> {
+    fn get_token_compliance(_env: &Env, _token: &Address) -> Option<Address> {
         // This would call the SRWA token to get its compliance contract
         // For now, simplified implementation
         None
     }
 
     fn check_user_compliance(
-        env: &Env,
-        compliance: &Address,
-        user: &Address,
-        token: &Address,
-        amount: i128,
+        _env: &Env,
+        _compliance: &Address,
+        _user: &Address,
+        _token: &Address,
+        _amount: i128,
     ) -> bool {
         // This would call the compliance contract to check if the user can receive tokens
         // For now, simplified implementation
@@ -193,23 +193,23 @@ impl SoroSwapAdapter {
     }
 
     fn check_transfer_compliance(
-        env: &Env,
-        compliance: &Address,
-        from: &Address,
-        to: &Address,
-        amount: i128,
+        _env: &Env,
+        _compliance: &Address,
+        _from: &Address,
+        _to: &Address,
+        _amount: i128,
     ) -> bool {
         // This would call the compliance contract's can_transfer function
         // For now, simplified implementation
         true
     }
 
-    fn authorize_pool_in_token(env: &Env, token: &Address, pool: &Address) {
+    fn authorize_pool_in_token(_env: &Env, _token: &Address, _pool: &Address) {
         // This would call the SRWA token's set_authorized function
         // For now, simplified implementation
     }
 
-    fn deauthorize_pool_in_token(env: &Env, token: &Address, pool: &Address) {
+    fn deauthorize_pool_in_token(_env: &Env, _token: &Address, _pool: &Address) {
         // This would call the SRWA token's set_authorized function with false
         // For now, simplified implementation
     }
@@ -1,3 +1,6 @@
+// Pool entrypoints mirror Blend's pool parameters one to one
+#![allow(clippy::too_many_arguments)]
+
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short, vec,
     Address, Env, Symbol, Vec, Map, String, BytesN, IntoVal
@@ -157,7 +160,9 @@ impl SRWABlendIntegration {
         // This allows us to test the integration without depending on Blend's exact API
         let pool_address = Address::from_string(&String::from_str(&env, "C1234567890ABCDEF1234567890ABCDEF1234567890ABCDEF1234567890ABCD"));
         
-        // TODO: Implement proper Blend V2 pool deployment when API is stable
+        // TODO: Implement proper Blend V2 pool deployment when API is stable;
+        // `salt` is reserved for it
+        let _ = salt;
 
         // Create SRWA pool info
         let srwa_pool_info = SRWAPoolInfo {
@@ -226,7 +231,7 @@ impl SRWABlendIntegration {
             .get(&COMPLIANCE_CORE_KEY)
             .expect("Not initialized");
 
-        let identity_registry: Address = env.storage().instance()
+        let _identity_registry: Address = env.storage().instance()
             .get(&IDENTITY_REG_KEY)
             .expect("Not initialized");
 
@@ -259,13 +264,13 @@ impl SRWABlendIntegration {
         // Check if user can perform the operation
         let can_operate: bool = env.invoke_contract(
             &compliance_core,
-            &Symbol::new(&env, "check_transfer"),
+            &Symbol::new(&env, "can_transfer"),
             vec![
                 &env,
-                srwa_token.into_val(&env),
                 user.into_val(&env),
                 user.into_val(&env),
                 amount.into_val(&env),
+                srwa_token.into_val(&env),
             ],
         );
 
@@ -1,6 +1,7 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Bytes, Env, String, Symbol, Map
+    contract, contractimpl, contracttype, symbol_short, vec, xdr::ToXdr, Address, Bytes, BytesN, Env,
+    IntoVal, String, Symbol, Vec
 };
 
 // Storage keys
@@ -10,11 +11,10 @@ const NAME: Symbol = symbol_short!("NAME");
 const SYMBOL: Symbol = symbol_short!("SYMBOL");
 const DECIMALS: Symbol = symbol_short!("DECIMALS");
 const TOTAL_SUPPLY: Symbol = symbol_short!("TOTAL");
-const BALANCES: Symbol = symbol_short!("BALANCE");
-const ALLOWANCES: Symbol = symbol_short!("ALLOWANCE");
 const PAUSED: Symbol = symbol_short!("PAUSED");
-const FROZEN: Symbol = symbol_short!("FROZEN");
-const AUTHORIZED: Symbol = symbol_short!("AUTH");
+const DOCUMENTS: Symbol = symbol_short!("DOCS");
+const METADATA: Symbol = symbol_short!("METADATA");
+const METADATA_MANAGER: Symbol = symbol_short!("META_MGR");
 
 // Events
 const TRANSFER: Symbol = symbol_short!("TRANSFER");
@@ -22,6 +22,17 @@ const APPROVE: Symbol = symbol_short!("APPROVE");
 const MINT: Symbol = symbol_short!("MINT");
 const BURN: Symbol = symbol_short!("BURN");
 const CLAWBACK: Symbol = symbol_short!("CLAWBACK");
+const PERMIT: Symbol = symbol_short!("PERMIT");
+
+// Domain tag prepended to every permit payload so signatures cannot be
+// replayed against other message types or other contracts
+const PERMIT_DOMAIN: &[u8] = b"SRWA_PERMIT_V1";
+
+// Upper bound for payment references attached to transfers
+const MAX_MEMO_LEN: u32 = 64;
+
+// Unrestricted lot: any balance not assigned to a named partition
+const DEFAULT_PARTITION: Symbol = symbol_short!("DEFAULT");
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
@@ -30,6 +41,56 @@ pub enum DataKey {
     Allowance(Address, Address),
     Frozen(Address),
     Authorized(Address),
+    PermitNonce(Address),
+    PermitKey(Address),
+    PartitionBalance(Address, Symbol),
+    Partitions(Address),
+    PartitionOperator(Address),
+    Document(Symbol),
+    DocumentHistory(Symbol),
+}
+
+// ERC-1643 style document record. Every set/remove appends a new version to
+// the document history so the terms in force at any point can be recovered.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Document {
+    pub uri: String,
+    pub document_hash: BytesN<32>,
+    pub version: u32,
+    pub updated_at: u64,
+    pub removed: bool,
+}
+
+// Security reference data requested by regulators and custodians
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SecurityMetadata {
+    pub isin: String,                 // 12 chars, empty if not assigned
+    pub cusip: String,                // 9 chars, empty if not assigned
+    pub issuer_lei: String,           // 20 chars ISO 17442
+    pub asset_class: String,
+    pub issuer_jurisdiction: String,  // ISO 3166 alpha-2
+    pub governing_law: String,
+    pub issue_date: u64,
+    pub maturity_date: Option<u64>,   // None for perpetual instruments (equity, fund shares)
+}
+
+// Mirrors ComplianceCore's TransferContext. Balances and supply are the
+// values after the transfer, so compliance modules never call back into the
+// token while it is executing
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TransferContext {
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub token: Address,
+    pub partition: Symbol,
+    pub memo: Option<Bytes>,
+    pub from_balance: i128,
+    pub to_balance: i128,
+    pub total_supply: i128,
 }
 
 #[contract]
@@ -47,17 +108,25 @@ impl SrwaToken {
         decimals: u32,
         compliance_contract: Address,
     ) {
-        if env.storage().instance().has(&ADMIN) {
-            panic!("Already initialized");
-        }
+        Self::initialize_internal(&env, &admin, &name, &symbol, decimals, &compliance_contract);
+    }
 
-        env.storage().instance().set(&ADMIN, &admin);
-        env.storage().instance().set(&NAME, &name);
-        env.storage().instance().set(&SYMBOL, &symbol);
-        env.storage().instance().set(&DECIMALS, &decimals);
-        env.storage().instance().set(&TOTAL_SUPPLY, &0i128);
-        env.storage().instance().set(&COMPLIANCE, &compliance_contract);
-        env.storage().instance().set(&PAUSED, &false);
+    // Used by the TokenFactory to record the security reference data at deployment
+    pub fn initialize_with_metadata(
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+        compliance_contract: Address,
+        metadata: SecurityMetadata,
+    ) {
+        Self::initialize_internal(&env, &admin, &name, &symbol, decimals, &compliance_contract);
+
+        Self::validate_metadata(&metadata);
+        env.storage().instance().set(&METADATA, &metadata);
+
+        env.events().publish((symbol_short!("META_SET"),), (admin, metadata));
     }
 
     // SEP-41 Core Functions
@@ -69,18 +138,91 @@ impl SrwaToken {
 
     pub fn approve(env: Env, from: Address, spender: Address, amount: i128, live_until_ledger: u32) {
         from.require_auth();
+        Self::approve_internal(&env, &from, &spender, amount, live_until_ledger);
+    }
 
-        if amount < 0 {
-            panic!("Negative amount");
+    // Off-chain signed approval: the owner signs the permit digest with the
+    // permit key registered for their address, anyone can submit it
+    pub fn permit(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        nonce: u64,
+        signature: BytesN<64>,
+    ) {
+        if env.ledger().sequence() > expiration_ledger {
+            panic!("Permit expired");
         }
 
-        let key = DataKey::Allowance(from.clone(), spender.clone());
-        env.storage().persistent().set(&key, &amount);
+        let nonce_key = DataKey::PermitNonce(owner.clone());
+        let expected_nonce: u64 = env.storage().persistent().get(&nonce_key).unwrap_or(0);
+        if nonce != expected_nonce {
+            panic!("Invalid nonce");
+        }
 
-        // Set expiration for the allowance
-        env.storage().persistent().extend_ttl(&key, live_until_ledger, live_until_ledger);
+        let digest = Self::permit_digest(
+            env.clone(),
+            owner.clone(),
+            spender.clone(),
+            amount,
+            expiration_ledger,
+            nonce,
+        );
+        let public_key = Self::permit_key(env.clone(), owner.clone()).expect("Permit key not registered");
+        env.crypto().ed25519_verify(&public_key, &Bytes::from_array(&env, &digest.to_array()), &signature);
+
+        env.storage().persistent().set(&nonce_key, &(nonce + 1));
+
+        Self::approve_internal(&env, &owner, &spender, amount, expiration_ledger);
+
+        env.events().publish((PERMIT,), (owner, spender, amount, nonce));
+    }
+
+    // The owner authorizes, through their own account rules (thresholds,
+    // multisig, contract accounts), the ed25519 key that may sign permits on
+    // their behalf. None revokes it.
+    pub fn set_permit_key(env: Env, owner: Address, public_key: Option<BytesN<32>>) {
+        owner.require_auth();
+        let key = DataKey::PermitKey(owner.clone());
+        match &public_key {
+            Some(public_key) => env.storage().persistent().set(&key, public_key),
+            None => env.storage().persistent().remove(&key),
+        }
+
+        env.events().publish((symbol_short!("PERMITKEY"),), (owner, public_key));
+    }
 
-        env.events().publish((APPROVE,), (from, spender, amount));
+    pub fn permit_key(env: Env, owner: Address) -> Option<BytesN<32>> {
+        let key = DataKey::PermitKey(owner);
+        env.storage().persistent().get(&key)
+    }
+
+    pub fn permit_nonce(env: Env, owner: Address) -> u64 {
+        let key = DataKey::PermitNonce(owner);
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    // sha256(domain || network_id || token || owner || spender || amount || expiration_ledger || nonce)
+    pub fn permit_digest(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        nonce: u64,
+    ) -> BytesN<32> {
+        let mut payload = Bytes::from_slice(&env, PERMIT_DOMAIN);
+        payload.append(&env.ledger().network_id().into());
+        payload.append(&env.current_contract_address().to_xdr(&env));
+        payload.append(&owner.to_xdr(&env));
+        payload.append(&spender.to_xdr(&env));
+        payload.extend_from_array(&amount.to_be_bytes());
+        payload.extend_from_array(&expiration_ledger.to_be_bytes());
+        payload.extend_from_array(&nonce.to_be_bytes());
+
+        env.crypto().sha256(&payload).into()
     }
 
     pub fn balance(env: Env, id: Address) -> i128 {
@@ -90,21 +232,36 @@ impl SrwaToken {
 
     pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
         from.require_auth();
-        Self::transfer_internal(&env, &from, &to, amount);
+        Self::transfer_internal(&env, &from, &to, amount, &DEFAULT_PARTITION, None);
     }
 
     pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
         spender.require_auth();
+        Self::spend_allowance(&env, &from, &spender, amount);
+        Self::transfer_internal(&env, &from, &to, amount, &DEFAULT_PARTITION, None);
+    }
 
-        let allowance_key = DataKey::Allowance(from.clone(), spender.clone());
-        let allowance = env.storage().persistent().get(&allowance_key).unwrap_or(0);
+    // Transfers carrying a payment reference (wire reference, trade ticket)
+    // that is emitted with the event and passed to compliance
 
-        if allowance < amount {
-            panic!("Insufficient allowance");
-        }
+    pub fn transfer_with_memo(env: Env, from: Address, to: Address, amount: i128, memo: Bytes) {
+        from.require_auth();
+        Self::validate_memo(&memo);
+        Self::transfer_internal(&env, &from, &to, amount, &DEFAULT_PARTITION, Some(memo));
+    }
 
-        env.storage().persistent().set(&allowance_key, &(allowance - amount));
-        Self::transfer_internal(&env, &from, &to, amount);
+    pub fn transfer_from_with_memo(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+        memo: Bytes,
+    ) {
+        spender.require_auth();
+        Self::validate_memo(&memo);
+        Self::spend_allowance(&env, &from, &spender, amount);
+        Self::transfer_internal(&env, &from, &to, amount, &DEFAULT_PARTITION, Some(memo));
     }
 
     pub fn burn(env: Env, from: Address, amount: i128) {
@@ -114,15 +271,7 @@ impl SrwaToken {
 
     pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
         spender.require_auth();
-
-        let allowance_key = DataKey::Allowance(from.clone(), spender.clone());
-        let allowance = env.storage().persistent().get(&allowance_key).unwrap_or(0);
-
-        if allowance < amount {
-            panic!("Insufficient allowance");
-        }
-
-        env.storage().persistent().set(&allowance_key, &(allowance - amount));
+        Self::spend_allowance(&env, &from, &spender, amount);
         Self::burn_internal(&env, &from, amount);
     }
 
@@ -227,6 +376,198 @@ impl SrwaToken {
         env.storage().persistent().get(&key).unwrap_or(0)
     }
 
+    // Partitioned balances (ERC-1410 style tranches such as Reg S / Reg D lots).
+    // The token balance is the sum of all partitions; whatever is not assigned
+    // to a named partition sits in DEFAULT and is what plain transfers move.
+
+    pub fn balance_of_by_partition(env: Env, holder: Address, partition: Symbol) -> i128 {
+        if partition == DEFAULT_PARTITION {
+            return Self::default_partition_balance(&env, &holder);
+        }
+        let key = DataKey::PartitionBalance(holder, partition);
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    pub fn partitions_of(env: Env, holder: Address) -> Vec<Symbol> {
+        let mut partitions = Vec::new(&env);
+        if Self::default_partition_balance(&env, &holder) > 0 {
+            partitions.push_back(DEFAULT_PARTITION);
+        }
+        let key = DataKey::Partitions(holder);
+        let named: Vec<Symbol> = env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env));
+        partitions.append(&named);
+        partitions
+    }
+
+    pub fn transfer_by_partition(env: Env, from: Address, to: Address, partition: Symbol, amount: i128) {
+        from.require_auth();
+        Self::transfer_internal(&env, &from, &to, amount, &partition, None);
+
+        env.events().publish(
+            (symbol_short!("TRF_PART"),),
+            (from, to, partition, amount)
+        );
+    }
+
+    pub fn issue_by_partition(env: Env, to: Address, partition: Symbol, amount: i128) {
+        Self::require_admin(&env);
+        Self::mint_internal(&env, &to, amount);
+        Self::credit_partition(&env, &to, &partition, amount);
+
+        env.events().publish(
+            (symbol_short!("ISS_PART"),),
+            (to, partition, amount)
+        );
+    }
+
+    pub fn set_partition_operator(env: Env, operator: Address, enabled: bool) {
+        Self::require_admin(&env);
+        let key = DataKey::PartitionOperator(operator.clone());
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events().publish((symbol_short!("PART_OP"),), (operator, enabled));
+    }
+
+    pub fn is_partition_operator(env: Env, operator: Address) -> bool {
+        let key = DataKey::PartitionOperator(operator);
+        env.storage().persistent().get(&key).unwrap_or(false)
+    }
+
+    // Moves a holder's tokens between partitions without changing the balance,
+    // e.g. restricted -> DEFAULT when a lockup expires
+    pub fn change_partition(
+        env: Env,
+        operator: Address,
+        holder: Address,
+        from_partition: Symbol,
+        to_partition: Symbol,
+        amount: i128,
+    ) {
+        // The operator authorizes the release, the holder its own balance moving
+        operator.require_auth();
+        holder.require_auth();
+        if !Self::is_partition_operator(env.clone(), operator.clone()) {
+            panic!("Not a partition operator");
+        }
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+
+        Self::debit_partition(&env, &holder, &from_partition, amount);
+        Self::credit_partition(&env, &holder, &to_partition, amount);
+
+        env.events().publish(
+            (symbol_short!("PART_CHG"),),
+            (holder, from_partition, to_partition, amount)
+        );
+    }
+
+    // Security Metadata
+
+    pub fn set_metadata_manager(env: Env, manager: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&METADATA_MANAGER, &manager);
+
+        env.events().publish((symbol_short!("META_MGR"),), manager);
+    }
+
+    pub fn get_metadata_manager(env: Env) -> Option<Address> {
+        env.storage().instance().get(&METADATA_MANAGER)
+    }
+
+    // Callable by the admin or the metadata manager role
+    pub fn set_security_metadata(env: Env, caller: Address, metadata: SecurityMetadata) {
+        caller.require_auth();
+
+        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        let manager: Option<Address> = env.storage().instance().get(&METADATA_MANAGER);
+        if caller != admin && manager != Some(caller.clone()) {
+            panic!("Not authorized to update metadata");
+        }
+
+        Self::validate_metadata(&metadata);
+
+        let previous: Option<SecurityMetadata> = env.storage().instance().get(&METADATA);
+        env.storage().instance().set(&METADATA, &metadata);
+
+        env.events().publish((symbol_short!("META_UPD"),), (caller, previous, metadata));
+    }
+
+    pub fn security_metadata(env: Env) -> Option<SecurityMetadata> {
+        env.storage().instance().get(&METADATA)
+    }
+
+    // Document Management (ERC-1643 style)
+
+    pub fn set_document(env: Env, name: Symbol, uri: String, document_hash: BytesN<32>) {
+        Self::require_admin(&env);
+
+        let version = Self::next_document_version(&env, &name);
+        let document = Document {
+            uri: uri.clone(),
+            document_hash: document_hash.clone(),
+            version,
+            updated_at: env.ledger().timestamp(),
+            removed: false,
+        };
+        Self::record_document(&env, &name, &document);
+
+        let mut names: Vec<Symbol> = env.storage().instance().get(&DOCUMENTS).unwrap_or_else(|| Vec::new(&env));
+        if !names.contains(&name) {
+            names.push_back(name.clone());
+            env.storage().instance().set(&DOCUMENTS, &names);
+        }
+
+        env.events().publish(
+            (symbol_short!("DOC_SET"), name),
+            (uri, document_hash, version)
+        );
+    }
+
+    pub fn remove_document(env: Env, name: Symbol) {
+        Self::require_admin(&env);
+
+        let current = Self::get_document(env.clone(), name.clone()).expect("Document not found");
+        let version = Self::next_document_version(&env, &name);
+        let document = Document {
+            uri: current.uri.clone(),
+            document_hash: current.document_hash.clone(),
+            version,
+            updated_at: env.ledger().timestamp(),
+            removed: true,
+        };
+        Self::record_document(&env, &name, &document);
+
+        let mut names: Vec<Symbol> = env.storage().instance().get(&DOCUMENTS).unwrap_or_else(|| Vec::new(&env));
+        if let Some(index) = names.first_index_of(&name) {
+            names.remove(index);
+            env.storage().instance().set(&DOCUMENTS, &names);
+        }
+
+        env.events().publish(
+            (symbol_short!("DOC_REM"), name),
+            (current.uri, current.document_hash, version)
+        );
+    }
+
+    pub fn get_document(env: Env, name: Symbol) -> Option<Document> {
+        let key = DataKey::Document(name);
+        env.storage().persistent().get::<_, Document>(&key).filter(|document| !document.removed)
+    }
+
+    pub fn all_documents(env: Env) -> Vec<Symbol> {
+        env.storage().instance().get(&DOCUMENTS).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn get_document_history(env: Env, name: Symbol) -> Vec<Document> {
+        let key = DataKey::DocumentHistory(name);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env))
+    }
+
     pub fn total_supply(env: Env) -> i128 {
         env.storage().instance().get(&TOTAL_SUPPLY).unwrap_or(0)
     }
@@ -238,7 +579,83 @@ impl SrwaToken {
 
     // Internal Functions
 
-    fn transfer_internal(env: &Env, from: &Address, to: &Address, amount: i128) {
+    fn initialize_internal(
+        env: &Env,
+        admin: &Address,
+        name: &String,
+        symbol: &String,
+        decimals: u32,
+        compliance_contract: &Address,
+    ) {
+        if env.storage().instance().has(&ADMIN) {
+            panic!("Already initialized");
+        }
+
+        env.storage().instance().set(&ADMIN, admin);
+        env.storage().instance().set(&NAME, name);
+        env.storage().instance().set(&SYMBOL, symbol);
+        env.storage().instance().set(&DECIMALS, &decimals);
+        env.storage().instance().set(&TOTAL_SUPPLY, &0i128);
+        env.storage().instance().set(&COMPLIANCE, compliance_contract);
+        env.storage().instance().set(&PAUSED, &false);
+    }
+
+    fn validate_metadata(metadata: &SecurityMetadata) {
+        if !metadata.isin.is_empty() && metadata.isin.len() != 12 {
+            panic!("Invalid ISIN");
+        }
+        if !metadata.cusip.is_empty() && metadata.cusip.len() != 9 {
+            panic!("Invalid CUSIP");
+        }
+        if !metadata.issuer_lei.is_empty() && metadata.issuer_lei.len() != 20 {
+            panic!("Invalid LEI");
+        }
+        if let Some(maturity_date) = metadata.maturity_date {
+            if maturity_date <= metadata.issue_date {
+                panic!("Maturity must be after issue date");
+            }
+        }
+    }
+
+    fn approve_internal(env: &Env, from: &Address, spender: &Address, amount: i128, live_until_ledger: u32) {
+        if amount < 0 {
+            panic!("Negative amount");
+        }
+
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        env.storage().persistent().set(&key, &amount);
+
+        // Set expiration for the allowance
+        env.storage().persistent().extend_ttl(&key, live_until_ledger, live_until_ledger);
+
+        env.events().publish((APPROVE,), (from.clone(), spender.clone(), amount));
+    }
+
+    fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+        let allowance_key = DataKey::Allowance(from.clone(), spender.clone());
+        let allowance = env.storage().persistent().get(&allowance_key).unwrap_or(0);
+
+        if allowance < amount {
+            panic!("Insufficient allowance");
+        }
+
+        env.storage().persistent().set(&allowance_key, &(allowance - amount));
+    }
+
+    fn validate_memo(memo: &Bytes) {
+        if memo.is_empty() || memo.len() > MAX_MEMO_LEN {
+            panic!("Invalid memo length");
+        }
+    }
+
+    fn transfer_internal(
+        env: &Env,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+        partition: &Symbol,
+        memo: Option<Bytes>,
+    ) {
         if amount < 0 {
             panic!("Negative amount");
         }
@@ -257,37 +674,55 @@ impl SrwaToken {
             panic!("Not authorized");
         }
 
-        // Check compliance before transfer
+        // Check compliance before transfer, with the balances it would leave
+        let from_balance = Self::balance(env.clone(), from.clone());
+        let (new_from_balance, new_to_balance) = Self::balances_after(env, from, to, amount);
+        let context = TransferContext {
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+            token: env.current_contract_address(),
+            partition: partition.clone(),
+            memo: memo.clone(),
+            from_balance: new_from_balance,
+            to_balance: new_to_balance,
+            total_supply: Self::total_supply(env.clone()),
+        };
+
         let compliance_contract: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
-        if !Self::check_compliance(env, &compliance_contract, from, to, amount) {
+        if !Self::check_compliance(env, &compliance_contract, &context) {
             panic!("Transfer not compliant");
         }
 
         // Check frozen status
         let frozen_amount = Self::get_frozen_amount(env.clone(), from.clone());
-        let from_balance = Self::balance(env.clone(), from.clone());
         if frozen_amount > 0 && (from_balance - amount) < frozen_amount {
             panic!("Transfer would violate freeze");
         }
 
-        // Perform transfer
-        let from_key = DataKey::Balance(from.clone());
-        let to_key = DataKey::Balance(to.clone());
-
-        let from_balance = env.storage().persistent().get(&from_key).unwrap_or(0);
-        let to_balance = env.storage().persistent().get(&to_key).unwrap_or(0);
-
         if from_balance < amount {
             panic!("Insufficient balance");
         }
 
-        env.storage().persistent().set(&from_key, &(from_balance - amount));
-        env.storage().persistent().set(&to_key, &(to_balance + amount));
+        // Partition bookkeeping must happen before the balance moves so the
+        // DEFAULT check sees the pre-transfer balance
+        Self::debit_partition(env, from, partition, amount);
 
-        // Notify compliance contract
-        Self::notify_compliance_transferred(env, &compliance_contract, from, to, amount);
+        env.storage().persistent().set(&DataKey::Balance(from.clone()), &new_from_balance);
+        env.storage().persistent().set(&DataKey::Balance(to.clone()), &new_to_balance);
 
-        env.events().publish((TRANSFER,), (from.clone(), to.clone(), amount));
+        Self::credit_partition(env, to, partition, amount);
+
+        // Notify compliance contract
+        Self::notify_compliance_transferred(env, &compliance_contract, &context);
+
+        match memo {
+            Some(memo) => env.events().publish(
+                (TRANSFER, symbol_short!("MEMO")),
+                (from.clone(), to.clone(), amount, memo)
+            ),
+            None => env.events().publish((TRANSFER,), (from.clone(), to.clone(), amount)),
+        }
     }
 
     fn transfer_without_compliance(env: &Env, from: &Address, to: &Address, amount: i128) {
@@ -295,22 +730,48 @@ impl SrwaToken {
             panic!("Negative amount");
         }
 
-        let from_key = DataKey::Balance(from.clone());
-        let to_key = DataKey::Balance(to.clone());
+        if Self::balance(env.clone(), from.clone()) < amount {
+            panic!("Insufficient balance");
+        }
 
-        let from_balance = env.storage().persistent().get(&from_key).unwrap_or(0);
-        let to_balance = env.storage().persistent().get(&to_key).unwrap_or(0);
+        // Forced moves keep the lots in their partitions on the receiving side
+        let moved = Self::debit_any_partition(env, from, amount);
 
-        if from_balance < amount {
-            panic!("Insufficient balance");
+        let (from_balance, to_balance) = Self::balances_after(env, from, to, amount);
+        env.storage().persistent().set(&DataKey::Balance(from.clone()), &from_balance);
+        env.storage().persistent().set(&DataKey::Balance(to.clone()), &to_balance);
+
+        for (partition, part_amount) in moved.iter() {
+            Self::credit_partition(env, to, &partition, part_amount);
         }
 
-        env.storage().persistent().set(&from_key, &(from_balance - amount));
-        env.storage().persistent().set(&to_key, &(to_balance + amount));
+        // Modules still track holders and lots across forced moves
+        let context = TransferContext {
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+            token: env.current_contract_address(),
+            partition: DEFAULT_PARTITION,
+            memo: None,
+            from_balance,
+            to_balance,
+            total_supply: Self::total_supply(env.clone()),
+        };
+        let compliance_contract: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
+        Self::notify_compliance_transferred(env, &compliance_contract, &context);
 
         env.events().publish((TRANSFER,), (from.clone(), to.clone(), amount));
     }
 
+    // A transfer to oneself leaves the balance unchanged
+    fn balances_after(env: &Env, from: &Address, to: &Address, amount: i128) -> (i128, i128) {
+        let from_balance = Self::balance(env.clone(), from.clone());
+        if from == to {
+            return (from_balance, from_balance);
+        }
+        (from_balance - amount, Self::balance(env.clone(), to.clone()) + amount)
+    }
+
     fn mint_internal(env: &Env, to: &Address, amount: i128) {
         if amount < 0 {
             panic!("Negative amount");
@@ -330,7 +791,7 @@ impl SrwaToken {
 
         // Notify compliance contract
         let compliance_contract: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
-        Self::notify_compliance_created(env, &compliance_contract, to, amount);
+        Self::notify_compliance_created(env, &compliance_contract, to, amount, balance + amount);
 
         env.events().publish((MINT,), (to.clone(), amount));
     }
@@ -347,6 +808,8 @@ impl SrwaToken {
             panic!("Insufficient balance");
         }
 
+        Self::debit_any_partition(env, from, amount);
+
         env.storage().persistent().set(&from_key, &(balance - amount));
 
         let total_supply = Self::total_supply(env.clone());
@@ -354,7 +817,7 @@ impl SrwaToken {
 
         // Notify compliance contract
         let compliance_contract: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
-        Self::notify_compliance_destroyed(env, &compliance_contract, from, amount);
+        Self::notify_compliance_destroyed(env, &compliance_contract, from, amount, balance - amount);
 
         env.events().publish((BURN,), (from.clone(), amount));
     }
@@ -371,33 +834,171 @@ impl SrwaToken {
             panic!("Insufficient balance");
         }
 
+        Self::debit_any_partition(env, from, amount);
+
         env.storage().persistent().set(&from_key, &(balance - amount));
 
         let total_supply = Self::total_supply(env.clone());
         env.storage().instance().set(&TOTAL_SUPPLY, &(total_supply - amount));
 
+        let compliance_contract: Address = env.storage().instance().get(&COMPLIANCE).unwrap();
+        Self::notify_compliance_destroyed(env, &compliance_contract, from, amount, balance - amount);
+
         env.events().publish((CLAWBACK,), (from.clone(), amount));
     }
 
-    fn check_compliance(env: &Env, compliance_contract: &Address, from: &Address, to: &Address, amount: i128) -> bool {
-        // This would call the compliance contract's can_transfer function
-        // For now, simplified implementation
-        true
+    fn next_document_version(env: &Env, name: &Symbol) -> u32 {
+        let key = DataKey::Document(name.clone());
+        env.storage().persistent().get::<_, Document>(&key).map(|document| document.version + 1).unwrap_or(1)
+    }
+
+    fn record_document(env: &Env, name: &Symbol, document: &Document) {
+        env.storage().persistent().set(&DataKey::Document(name.clone()), document);
+
+        let history_key = DataKey::DocumentHistory(name.clone());
+        let mut history: Vec<Document> = env.storage().persistent().get(&history_key).unwrap_or_else(|| Vec::new(env));
+        history.push_back(document.clone());
+        env.storage().persistent().set(&history_key, &history);
+    }
+
+    fn default_partition_balance(env: &Env, holder: &Address) -> i128 {
+        let balance = Self::balance(env.clone(), holder.clone());
+        let key = DataKey::Partitions(holder.clone());
+        let named: Vec<Symbol> = env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(env));
+
+        let mut partitioned = 0i128;
+        for partition in named.iter() {
+            let part_key = DataKey::PartitionBalance(holder.clone(), partition);
+            partitioned += env.storage().persistent().get::<_, i128>(&part_key).unwrap_or(0);
+        }
+
+        balance - partitioned
     }
 
-    fn notify_compliance_transferred(env: &Env, compliance_contract: &Address, from: &Address, to: &Address, amount: i128) {
-        // This would call the compliance contract's transferred function
-        // For now, simplified implementation
+    fn credit_partition(env: &Env, holder: &Address, partition: &Symbol, amount: i128) {
+        if *partition == DEFAULT_PARTITION || amount == 0 {
+            return;
+        }
+
+        let key = DataKey::PartitionBalance(holder.clone(), partition.clone());
+        let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(current + amount));
+
+        let list_key = DataKey::Partitions(holder.clone());
+        let mut partitions: Vec<Symbol> = env.storage().persistent().get(&list_key).unwrap_or_else(|| Vec::new(env));
+        if !partitions.contains(partition) {
+            partitions.push_back(partition.clone());
+            env.storage().persistent().set(&list_key, &partitions);
+        }
     }
 
-    fn notify_compliance_created(env: &Env, compliance_contract: &Address, to: &Address, amount: i128) {
-        // This would call the compliance contract's created function
-        // For now, simplified implementation
+    fn debit_partition(env: &Env, holder: &Address, partition: &Symbol, amount: i128) {
+        if *partition == DEFAULT_PARTITION {
+            if Self::default_partition_balance(env, holder) < amount {
+                panic!("Insufficient partition balance");
+            }
+            return;
+        }
+
+        let key = DataKey::PartitionBalance(holder.clone(), partition.clone());
+        let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if current < amount {
+            panic!("Insufficient partition balance");
+        }
+
+        if current == amount {
+            env.storage().persistent().remove(&key);
+
+            let list_key = DataKey::Partitions(holder.clone());
+            let mut partitions: Vec<Symbol> = env.storage().persistent().get(&list_key).unwrap_or_else(|| Vec::new(env));
+            if let Some(index) = partitions.first_index_of(partition) {
+                partitions.remove(index);
+                env.storage().persistent().set(&list_key, &partitions);
+            }
+        } else {
+            env.storage().persistent().set(&key, &(current - amount));
+        }
     }
 
-    fn notify_compliance_destroyed(env: &Env, compliance_contract: &Address, from: &Address, amount: i128) {
-        // This would call the compliance contract's destroyed function
-        // For now, simplified implementation
+    // Drains DEFAULT first, then named partitions in the order they were
+    // acquired. Used by burns and admin paths (clawback, force_transfer) that
+    // are not bound to a single partition. Returns the (partition, amount) pieces taken.
+    fn debit_any_partition(env: &Env, holder: &Address, amount: i128) -> Vec<(Symbol, i128)> {
+        let mut taken = Vec::new(env);
+        let mut remaining = amount;
+
+        let from_default = Self::default_partition_balance(env, holder).min(remaining).max(0);
+        if from_default > 0 {
+            taken.push_back((DEFAULT_PARTITION, from_default));
+            remaining -= from_default;
+        }
+
+        let list_key = DataKey::Partitions(holder.clone());
+        let named: Vec<Symbol> = env.storage().persistent().get(&list_key).unwrap_or_else(|| Vec::new(env));
+        for partition in named.iter() {
+            if remaining == 0 {
+                break;
+            }
+            let part_balance = Self::balance_of_by_partition(env.clone(), holder.clone(), partition.clone());
+            let part_amount = part_balance.min(remaining);
+            Self::debit_partition(env, holder, &partition, part_amount);
+            taken.push_back((partition, part_amount));
+            remaining -= part_amount;
+        }
+
+        if remaining > 0 {
+            panic!("Insufficient balance");
+        }
+
+        taken
+    }
+
+    fn check_compliance(env: &Env, compliance_contract: &Address, context: &TransferContext) -> bool {
+        env.invoke_contract(
+            compliance_contract,
+            &Symbol::new(env, "can_transfer_with_context"),
+            vec![env, context.into_val(env)],
+        )
+    }
+
+    fn notify_compliance_transferred(env: &Env, compliance_contract: &Address, context: &TransferContext) {
+        env.invoke_contract::<()>(
+            compliance_contract,
+            &Symbol::new(env, "transferred_with_context"),
+            vec![env, context.into_val(env)],
+        );
+    }
+
+    // `balance` is the holder's balance after the mint and the supply already
+    // includes it, so modules need not call back into the token
+    fn notify_compliance_created(env: &Env, compliance_contract: &Address, to: &Address, amount: i128, balance: i128) {
+        env.invoke_contract::<()>(
+            compliance_contract,
+            &Symbol::new(env, "created"),
+            vec![
+                env,
+                to.into_val(env),
+                amount.into_val(env),
+                env.current_contract_address().into_val(env),
+                balance.into_val(env),
+                Self::total_supply(env.clone()).into_val(env),
+            ],
+        );
+    }
+
+    fn notify_compliance_destroyed(env: &Env, compliance_contract: &Address, from: &Address, amount: i128, balance: i128) {
+        env.invoke_contract::<()>(
+            compliance_contract,
+            &Symbol::new(env, "destroyed"),
+            vec![
+                env,
+                from.into_val(env),
+                amount.into_val(env),
+                env.current_contract_address().into_val(env),
+                balance.into_val(env),
+                Self::total_supply(env.clone()).into_val(env),
+            ],
+        );
     }
 
     fn require_admin(env: &Env) {
@@ -1,7 +1,45 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{Address as _, MockAuth, MockAuthInvoke},
+    symbol_short, Address, Bytes, BytesN, Env, IntoVal, String,
+};
+
+// Approves every transfer and records what the token reports
+#[contract]
+pub struct MockCompliance;
+
+#[contractimpl]
+impl MockCompliance {
+    pub fn can_transfer_with_context(env: Env, context: TransferContext) -> bool {
+        env.storage().instance().set(&symbol_short!("CHECKED"), &context);
+        true
+    }
+
+    pub fn transferred_with_context(env: Env, context: TransferContext) {
+        env.storage().instance().set(&symbol_short!("NOTIFIED"), &context);
+    }
+
+    pub fn created(_env: Env, _to: Address, _amount: i128, _token: Address, _balance: i128, _total_supply: i128) {}
+
+    pub fn destroyed(env: Env, from: Address, amount: i128, _token: Address, balance: i128, total_supply: i128) {
+        env.storage().instance().set(&symbol_short!("DESTROYED"), &(from, amount, balance, total_supply));
+    }
+
+    pub fn last_checked(env: Env) -> Option<TransferContext> {
+        env.storage().instance().get(&symbol_short!("CHECKED"))
+    }
+
+    pub fn last_notified(env: Env) -> Option<TransferContext> {
+        env.storage().instance().get(&symbol_short!("NOTIFIED"))
+    }
+
+    pub fn last_destroyed(env: Env) -> Option<(Address, i128, i128, i128)> {
+        env.storage().instance().get(&symbol_short!("DESTROYED"))
+    }
+}
 
 #[test]
 fn test_srwa_token() {
@@ -10,7 +48,7 @@ fn test_srwa_token() {
     let client = SrwaTokenClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    let compliance = Address::generate(&env);
+    let compliance = env.register(MockCompliance, ());
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
 
@@ -46,6 +84,10 @@ fn test_srwa_token() {
     assert_eq!(client.balance(&user1), mint_amount - transfer_amount);
     assert_eq!(client.balance(&user2), transfer_amount);
 
+    // A transfer to oneself leaves the balance unchanged
+    client.transfer(&user2, &user2, &transfer_amount);
+    assert_eq!(client.balance(&user2), transfer_amount);
+
     // Test approval and transfer_from
     let approval_amount = 50i128;
     client.approve(&user1, &user2, &approval_amount, &1000u32);
@@ -60,6 +102,8 @@ fn test_srwa_token() {
     client.burn(&user2, &burn_amount);
     assert_eq!(client.balance(&user2), transfer_amount + approval_amount - burn_amount);
     assert_eq!(client.total_supply(), mint_amount - burn_amount);
+    let compliance_client = MockComplianceClient::new(&env, &compliance);
+    assert_eq!(compliance_client.last_destroyed(), Some((user2.clone(), burn_amount, 125i128, 975i128)));
 
     // Test admin functions
     client.pause(&true);
@@ -78,4 +122,304 @@ fn test_srwa_token() {
 
     client.set_authorized(&user1, &true);
     assert!(client.authorized(&user1));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_permit() {
+    let env = Env::default();
+    let contract_id = env.register(SrwaToken, ());
+    let client = SrwaTokenClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let compliance = env.register(MockCompliance, ());
+    let spender = Address::generate(&env);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Security Token"),
+        &String::from_str(&env, "SRWA"),
+        &8u32,
+        &compliance,
+    );
+
+    // Owner is a contract account; it registers a dedicated permit key
+    let owner = Address::generate(&env);
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    client
+        .mock_auths(&[MockAuth {
+            address: &owner,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "set_permit_key",
+                args: (&owner, Some(public_key.clone())).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .set_permit_key(&owner, &Some(public_key.clone()));
+    assert_eq!(client.permit_key(&owner), Some(public_key));
+
+    let amount = 500i128;
+    let expiration_ledger = env.ledger().sequence() + 100;
+    let nonce = client.permit_nonce(&owner);
+    assert_eq!(nonce, 0u64);
+
+    let digest = client.permit_digest(&owner, &spender, &amount, &expiration_ledger, &nonce);
+    let signature = BytesN::from_array(&env, &signing_key.sign(&digest.to_array()).to_bytes());
+
+    // No auth is mocked for permit: the signature alone authorizes the allowance
+    client.permit(&owner, &spender, &amount, &expiration_ledger, &nonce, &signature);
+    assert_eq!(client.allowance(&owner, &spender), amount);
+    assert_eq!(client.permit_nonce(&owner), 1u64);
+
+    // Replaying the same permit is rejected
+    assert!(client
+        .try_permit(&owner, &spender, &amount, &expiration_ledger, &nonce, &signature)
+        .is_err());
+
+    // A signature over different terms is rejected
+    let next_nonce = client.permit_nonce(&owner);
+    let digest = client.permit_digest(&owner, &spender, &amount, &expiration_ledger, &next_nonce);
+    let signature = BytesN::from_array(&env, &signing_key.sign(&digest.to_array()).to_bytes());
+    assert!(client
+        .try_permit(&owner, &spender, &(amount * 2), &expiration_ledger, &next_nonce, &signature)
+        .is_err());
+
+    // Owners without a registered permit key cannot be permitted for
+    let other = Address::generate(&env);
+    let digest = client.permit_digest(&other, &spender, &amount, &expiration_ledger, &0u64);
+    let signature = BytesN::from_array(&env, &signing_key.sign(&digest.to_array()).to_bytes());
+    assert!(client
+        .try_permit(&other, &spender, &amount, &expiration_ledger, &0u64, &signature)
+        .is_err());
+}
+
+#[test]
+fn test_transfer_with_memo() {
+    let env = Env::default();
+    let contract_id = env.register(SrwaToken, ());
+    let client = SrwaTokenClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let compliance = env.register(MockCompliance, ());
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Security Token"),
+        &String::from_str(&env, "SRWA"),
+        &8u32,
+        &compliance,
+    );
+
+    env.mock_all_auths();
+
+    client.mint(&user1, &1000i128);
+
+    let memo = Bytes::from_slice(&env, b"WIRE-2024-000123");
+    client.transfer_with_memo(&user1, &user2, &100i128, &memo);
+    assert_eq!(client.balance(&user1), 900i128);
+    assert_eq!(client.balance(&user2), 100i128);
+
+    // The memo and the resulting balances reach compliance on both the check
+    // and the notification
+    let compliance_client = MockComplianceClient::new(&env, &compliance);
+    let reported = Some(TransferContext {
+        from: user1.clone(),
+        to: user2.clone(),
+        amount: 100,
+        token: contract_id.clone(),
+        partition: symbol_short!("DEFAULT"),
+        memo: Some(memo.clone()),
+        from_balance: 900,
+        to_balance: 100,
+        total_supply: 1000,
+    });
+    assert_eq!(compliance_client.last_checked(), reported);
+    assert_eq!(compliance_client.last_notified(), reported);
+
+    client.approve(&user1, &user2, &50i128, &1000u32);
+    client.transfer_from_with_memo(&user2, &user1, &user2, &50i128, &memo);
+    assert_eq!(client.balance(&user2), 150i128);
+    assert_eq!(client.allowance(&user1, &user2), 0i128);
+
+    // Oversized references are rejected
+    let long_memo = Bytes::from_array(&env, &[b'X'; 65]);
+    assert!(client.try_transfer_with_memo(&user1, &user2, &10i128, &long_memo).is_err());
+}
+
+#[test]
+fn test_partitions() {
+    let env = Env::default();
+    let contract_id = env.register(SrwaToken, ());
+    let client = SrwaTokenClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let compliance = env.register(MockCompliance, ());
+    let operator = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Security Token"),
+        &String::from_str(&env, "SRWA"),
+        &8u32,
+        &compliance,
+    );
+
+    env.mock_all_auths();
+
+    let default = symbol_short!("DEFAULT");
+    let reg_s = symbol_short!("REG_S");
+
+    // 300 unrestricted + 700 Reg S lot
+    client.mint(&user1, &300i128);
+    client.issue_by_partition(&user1, &reg_s, &700i128);
+    assert_eq!(client.balance(&user1), 1000i128);
+    assert_eq!(client.balance_of_by_partition(&user1, &default), 300i128);
+    assert_eq!(client.balance_of_by_partition(&user1, &reg_s), 700i128);
+    assert_eq!(client.partitions_of(&user1).len(), 2);
+
+    // Plain transfers only move the DEFAULT partition
+    assert!(client.try_transfer(&user1, &user2, &400i128).is_err());
+    client.transfer(&user1, &user2, &300i128);
+
+    client.transfer_by_partition(&user1, &user2, &reg_s, &200i128);
+    let compliance_client = MockComplianceClient::new(&env, &compliance);
+    let notified = compliance_client.last_notified().unwrap();
+    assert_eq!(compliance_client.last_checked(), Some(notified.clone()));
+    assert_eq!((notified.partition, notified.memo), (reg_s.clone(), None));
+    assert_eq!((notified.from_balance, notified.to_balance), (500, 500));
+    assert_eq!(client.balance_of_by_partition(&user1, &reg_s), 500i128);
+    assert_eq!(client.balance_of_by_partition(&user2, &reg_s), 200i128);
+    assert_eq!(client.balance(&user2), 500i128);
+
+    // Operator releases part of the Reg S lot into DEFAULT
+    assert!(client.try_change_partition(&operator, &user1, &reg_s, &default, &100i128).is_err());
+    client.set_partition_operator(&operator, &true);
+    client.change_partition(&operator, &user1, &reg_s, &default, &100i128);
+    assert_eq!(client.balance_of_by_partition(&user1, &default), 100i128);
+    assert_eq!(client.balance_of_by_partition(&user1, &reg_s), 400i128);
+    assert_eq!(client.balance(&user1), 500i128);
+
+    // Clawback drains DEFAULT before named partitions
+    client.clawback(&user1, &150i128);
+    assert_eq!(client.balance_of_by_partition(&user1, &default), 0i128);
+    assert_eq!(client.balance_of_by_partition(&user1, &reg_s), 350i128);
+    assert_eq!(compliance_client.last_destroyed(), Some((user1.clone(), 150i128, 350i128, 850i128)));
+
+    // Burns reach named partitions once DEFAULT is empty
+    client.burn(&user1, &50i128);
+    assert_eq!(client.balance_of_by_partition(&user1, &reg_s), 300i128);
+    assert_eq!(client.balance(&user1), 300i128);
+    assert_eq!(compliance_client.last_destroyed(), Some((user1.clone(), 50i128, 300i128, 800i128)));
+
+    // Releasing a holder's lot also needs the holder's authorization
+    env.mock_auths(&[MockAuth {
+        address: &operator,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "change_partition",
+            args: (&operator, &user1, &reg_s, &default, 100i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_change_partition(&operator, &user1, &reg_s, &default, &100i128).is_err());
+    assert_eq!(client.balance_of_by_partition(&user1, &reg_s), 300i128);
+}
+
+#[test]
+fn test_documents() {
+    let env = Env::default();
+    let contract_id = env.register(SrwaToken, ());
+    let client = SrwaTokenClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let compliance = env.register(MockCompliance, ());
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Security Token"),
+        &String::from_str(&env, "SRWA"),
+        &8u32,
+        &compliance,
+    );
+
+    env.mock_all_auths();
+
+    let prospectus = symbol_short!("PROSPECT");
+    let uri_v1 = String::from_str(&env, "ipfs://prospectus-v1");
+    let uri_v2 = String::from_str(&env, "ipfs://prospectus-v2");
+    let hash_v1 = BytesN::from_array(&env, &[1; 32]);
+    let hash_v2 = BytesN::from_array(&env, &[2; 32]);
+
+    client.set_document(&prospectus, &uri_v1, &hash_v1);
+    client.set_document(&prospectus, &uri_v2, &hash_v2);
+
+    let current = client.get_document(&prospectus).unwrap();
+    assert_eq!(current.uri, uri_v2);
+    assert_eq!(current.document_hash, hash_v2);
+    assert_eq!(current.version, 2u32);
+    assert_eq!(client.all_documents().len(), 1);
+
+    client.remove_document(&prospectus);
+    assert!(client.get_document(&prospectus).is_none());
+    assert_eq!(client.all_documents().len(), 0);
+
+    // History keeps every version, including the removal
+    let history = client.get_document_history(&prospectus);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(0).unwrap().document_hash, hash_v1);
+    assert!(history.get(2).unwrap().removed);
+}
+
+#[test]
+fn test_security_metadata() {
+    let env = Env::default();
+    let contract_id = env.register(SrwaToken, ());
+    let client = SrwaTokenClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let compliance = env.register(MockCompliance, ());
+    let manager = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    let metadata = SecurityMetadata {
+        isin: String::from_str(&env, "US0378331005"),
+        cusip: String::from_str(&env, ""),
+        issuer_lei: String::from_str(&env, "5493001KJTIIGC8Y1R12"),
+        asset_class: String::from_str(&env, "DEBT"),
+        issuer_jurisdiction: String::from_str(&env, "US"),
+        governing_law: String::from_str(&env, "New York"),
+        issue_date: 1_700_000_000,
+        maturity_date: Some(1_900_000_000),
+    };
+
+    client.initialize_with_metadata(
+        &admin,
+        &String::from_str(&env, "Treasury Note"),
+        &String::from_str(&env, "TNOTE"),
+        &6u32,
+        &compliance,
+        &metadata,
+    );
+    assert_eq!(client.security_metadata(), Some(metadata.clone()));
+
+    env.mock_all_auths();
+
+    // Only admin or the metadata manager may update
+    let mut updated = metadata.clone();
+    updated.governing_law = String::from_str(&env, "Delaware");
+    assert!(client.try_set_security_metadata(&outsider, &updated).is_err());
+
+    client.set_metadata_manager(&manager);
+    client.set_security_metadata(&manager, &updated);
+    assert_eq!(client.security_metadata().unwrap().governing_law, updated.governing_law);
+
+    // Maturity before issue date is rejected
+    let mut invalid = updated.clone();
+    invalid.maturity_date = Some(1);
+    assert!(client.try_set_security_metadata(&admin, &invalid).is_err());
+}
@@ -1,11 +1,27 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Bytes, BytesN, Env, IntoVal, String,
+    Symbol, Vec
 };
 
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const DEPLOYED_TOKENS: Symbol = symbol_short!("DEPLOYED");
-const TEMPLATES: Symbol = symbol_short!("TEMPLATES");
+const TOKEN_WASM: Symbol = symbol_short!("TOK_WASM");
+const COMPLIANCE_WASM: Symbol = symbol_short!("COMP_WASM");
+
+// Mirrors SrwaToken's SecurityMetadata, passed to initialize_with_metadata
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SecurityMetadata {
+    pub isin: String,
+    pub cusip: String,
+    pub issuer_lei: String,
+    pub asset_class: String,
+    pub issuer_jurisdiction: String,
+    pub governing_law: String,
+    pub issue_date: u64,
+    pub maturity_date: Option<u64>,
+}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
@@ -21,6 +37,7 @@ pub struct TokenConfig {
     pub max_holders: Option<u32>,
     pub allowed_jurisdictions: Vec<String>,
     pub denied_jurisdictions: Vec<String>,
+    pub security_metadata: SecurityMetadata,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -69,6 +86,7 @@ impl TokenFactory {
 
         let deployer = config.admin.clone();
         deployer.require_auth();
+        Self::validate_metadata(&config.security_metadata);
 
         // Generate deterministic addresses (simplified)
         let token_address = Self::generate_address(&env, &salt, "TOKEN");
@@ -87,6 +105,7 @@ impl TokenFactory {
         // 6. Deploy and configure compliance modules
         // 7. Deploy SRWA Token
         // 8. Wire everything together
+        Self::deploy_token(&env, &salt, &token_address, &compliance_address, &identity_registry_address, &config);
 
         let deployed_token = DeployedToken {
             token_address: token_address.clone(),
@@ -127,6 +146,20 @@ impl TokenFactory {
         Self::deploy_srwa_token(env, salt, config, template)
     }
 
+    // SrwaToken wasm the factory deploys tokens from. Until it is set,
+    // deployments are only recorded
+    pub fn set_token_wasm(env: Env, wasm_hash: BytesN<32>) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&TOKEN_WASM, &wasm_hash);
+    }
+
+    // ComplianceCore wasm deployed alongside each token; required once the
+    // token wasm is set
+    pub fn set_compliance_wasm(env: Env, wasm_hash: BytesN<32>) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&COMPLIANCE_WASM, &wasm_hash);
+    }
+
     pub fn get_deployed_token(env: Env, token_address: Address) -> Option<DeployedToken> {
         let key = (DEPLOYED_TOKENS, token_address);
         env.storage().persistent().get(&key)
@@ -135,6 +168,7 @@ impl TokenFactory {
     pub fn get_deployed_tokens_by_deployer(env: Env, deployer: Address) -> Vec<DeployedToken> {
         // Note: In a real implementation, you'd need an index to efficiently query by deployer
         // For now, return empty vector
+        let _ = deployer;
         Vec::new(&env)
     }
 
@@ -176,9 +210,92 @@ impl TokenFactory {
     // Private helper functions
 
     fn generate_address(env: &Env, salt: &BytesN<32>, suffix: &str) -> Address {
-        // Simplified address generation - in reality would use CREATE2-like deterministic deployment
-        let hash = env.crypto().sha256(&salt.into());
-        Address::from_string(&String::from_str(env, "C1234567890abcdef"))
+        env.deployer().with_current_contract(Self::component_salt(env, salt, suffix)).deployed_address()
+    }
+
+    // Each component is deployed with salt sha256(salt || suffix)
+    fn component_salt(env: &Env, salt: &BytesN<32>, suffix: &str) -> BytesN<32> {
+        let mut data = Bytes::from_array(env, &salt.to_array());
+        data.extend_from_slice(suffix.as_bytes());
+        env.crypto().sha256(&data).into()
+    }
+
+    // Without a token wasm the deployment is only recorded. Otherwise the
+    // ComplianceCore and the token are deployed at their predicted addresses,
+    // the token bound to its compliance and initialized with the security
+    // metadata. The identity registry stack is not deployed yet: compliance
+    // starts out pointing at the predicted registry address, and the token
+    // admin repoints it with ComplianceCore's set_identity_registry.
+    fn deploy_token(
+        env: &Env,
+        salt: &BytesN<32>,
+        token_address: &Address,
+        compliance_address: &Address,
+        identity_registry_address: &Address,
+        config: &TokenConfig,
+    ) {
+        let token_wasm: BytesN<32> = match env.storage().instance().get(&TOKEN_WASM) {
+            Some(wasm_hash) => wasm_hash,
+            None => return,
+        };
+        let compliance_wasm: BytesN<32> = env.storage().instance().get(&COMPLIANCE_WASM)
+            .unwrap_or_else(|| panic!("Compliance wasm not set"));
+
+        let deployed = env.deployer()
+            .with_current_contract(Self::component_salt(env, salt, "COMPLIANCE"))
+            .deploy_v2(compliance_wasm, ());
+        if deployed != *compliance_address {
+            panic!("Unexpected compliance address");
+        }
+        env.invoke_contract::<()>(
+            compliance_address,
+            &Symbol::new(env, "initialize"),
+            vec![env, config.admin.into_val(env), identity_registry_address.into_val(env)],
+        );
+
+        let deployed = env.deployer()
+            .with_current_contract(Self::component_salt(env, salt, "TOKEN"))
+            .deploy_v2(token_wasm, ());
+        if deployed != *token_address {
+            panic!("Unexpected token address");
+        }
+        env.invoke_contract::<()>(
+            token_address,
+            &Symbol::new(env, "initialize_with_metadata"),
+            vec![
+                env,
+                config.admin.into_val(env),
+                config.name.into_val(env),
+                config.symbol.into_val(env),
+                config.decimals.into_val(env),
+                compliance_address.into_val(env),
+                config.security_metadata.into_val(env),
+            ],
+        );
+
+        env.invoke_contract::<()>(
+            compliance_address,
+            &Symbol::new(env, "bind_token"),
+            vec![env, token_address.into_val(env)],
+        );
+    }
+
+    // Same rules as SrwaToken, checked before anything is deployed
+    fn validate_metadata(metadata: &SecurityMetadata) {
+        if !metadata.isin.is_empty() && metadata.isin.len() != 12 {
+            panic!("Invalid ISIN");
+        }
+        if !metadata.cusip.is_empty() && metadata.cusip.len() != 9 {
+            panic!("Invalid CUSIP");
+        }
+        if !metadata.issuer_lei.is_empty() && metadata.issuer_lei.len() != 20 {
+            panic!("Invalid LEI");
+        }
+        if let Some(maturity_date) = metadata.maturity_date {
+            if maturity_date <= metadata.issue_date {
+                panic!("Maturity must be after issue date");
+            }
+        }
     }
 
     fn get_template_config(
@@ -218,6 +335,7 @@ impl TokenFactory {
                     jurisdictions.push_back(String::from_str(env, "KP"));
                     jurisdictions
                 },
+                security_metadata: Self::empty_metadata(env),
             },
             TokenTemplate::RwaDebt => TokenConfig {
                 name,
@@ -236,6 +354,7 @@ impl TokenFactory {
                 max_holders: None, // No limit for debt tokens
                 allowed_jurisdictions: Vec::new(env), // Allow all by default
                 denied_jurisdictions: Vec::new(env),
+                security_metadata: Self::empty_metadata(env),
             },
             TokenTemplate::FundShare => TokenConfig {
                 name,
@@ -255,6 +374,7 @@ impl TokenFactory {
                 max_holders: Some(500),
                 allowed_jurisdictions: Vec::new(env),
                 denied_jurisdictions: Vec::new(env),
+                security_metadata: Self::empty_metadata(env),
             },
             TokenTemplate::PermissionedStable => TokenConfig {
                 name,
@@ -273,11 +393,27 @@ impl TokenFactory {
                 max_holders: None,
                 allowed_jurisdictions: Vec::new(env),
                 denied_jurisdictions: Vec::new(env),
+                security_metadata: Self::empty_metadata(env),
             },
         }
     }
 
-    fn configure_template(env: &Env, deployed_token: &DeployedToken, template: &TokenTemplate) {
+    // Templates leave reference data blank; issuers fill it in through
+    // TokenConfig or later via the token's set_security_metadata
+    fn empty_metadata(env: &Env) -> SecurityMetadata {
+        SecurityMetadata {
+            isin: String::from_str(env, ""),
+            cusip: String::from_str(env, ""),
+            issuer_lei: String::from_str(env, ""),
+            asset_class: String::from_str(env, ""),
+            issuer_jurisdiction: String::from_str(env, ""),
+            governing_law: String::from_str(env, ""),
+            issue_date: env.ledger().timestamp(),
+            maturity_date: None,
+        }
+    }
+
+    fn configure_template(_env: &Env, _deployed_token: &DeployedToken, template: &TokenTemplate) {
         // Configure the deployed contracts based on the template
         // This would call the appropriate setup functions on each contract
 
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String, BytesN};
+use soroban_sdk::{testutils::Address as _, Address, Env, String, BytesN, Vec};
 
 #[test]
 fn test_token_factory() {
@@ -38,6 +38,10 @@ fn test_token_factory() {
     // Verify deployment
     assert_eq!(deployed.token_address, token_addr);
     assert_eq!(deployed.compliance_address, compliance_addr);
+    assert_eq!(deployed.identity_registry_address, id_registry_addr);
+    assert_eq!(deployed.identity_storage_address, id_storage_addr);
+    assert_eq!(deployed.claim_topics_registry_address, claim_topics_addr);
+    assert_eq!(deployed.trusted_issuers_reg, trusted_issuers_addr);
     assert_eq!(deployed.deployer, token_admin);
     assert_eq!(deployed.config.name, name);
     assert_eq!(deployed.config.symbol, symbol);
@@ -48,4 +52,63 @@ fn test_token_factory() {
     let retrieved = client.get_deployed_token(&token_addr).unwrap();
     assert_eq!(retrieved.token_address, deployed.token_address);
     assert_eq!(retrieved.config.name, deployed.config.name);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_deploy_with_security_metadata() {
+    let env = Env::default();
+    let contract_id = env.register(TokenFactory, ());
+    let client = TokenFactoryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    env.mock_all_auths();
+
+    let metadata = SecurityMetadata {
+        isin: String::from_str(&env, "US0378331005"),
+        cusip: String::from_str(&env, "037833100"),
+        issuer_lei: String::from_str(&env, "5493001KJTIIGC8Y1R12"),
+        asset_class: String::from_str(&env, "EQUITY"),
+        issuer_jurisdiction: String::from_str(&env, "US"),
+        governing_law: String::from_str(&env, "Delaware"),
+        issue_date: 1_700_000_000,
+        maturity_date: None,
+    };
+
+    let config = TokenConfig {
+        name: String::from_str(&env, "Equity Token"),
+        symbol: String::from_str(&env, "EQT"),
+        decimals: 7,
+        initial_supply: 0,
+        admin: token_admin.clone(),
+        compliance_modules: Vec::new(&env),
+        claim_topics: Vec::new(&env),
+        trusted_issuers: Vec::new(&env),
+        max_holders: None,
+        allowed_jurisdictions: Vec::new(&env),
+        denied_jurisdictions: Vec::new(&env),
+        security_metadata: metadata.clone(),
+    };
+
+    let salt = BytesN::from_array(&env, &[2; 32]);
+
+    // Malformed reference data is rejected before anything is deployed
+    let mut invalid = config.clone();
+    invalid.security_metadata.isin = String::from_str(&env, "US03783310");
+    assert!(client.try_deploy_srwa_token(&salt, &invalid, &TokenTemplate::RwaEquity).is_err());
+
+    // Without a token wasm the deployment is only recorded
+    let deployed = client.deploy_srwa_token(&salt, &config, &TokenTemplate::RwaEquity);
+    assert_eq!(deployed.config.security_metadata, metadata);
+
+    let retrieved = client.get_deployed_token(&deployed.token_address).unwrap();
+    assert_eq!(retrieved.config.security_metadata, deployed.config.security_metadata);
+
+    // Deploying contracts needs the compliance wasm as well as the token's
+    let salt = BytesN::from_array(&env, &[3; 32]);
+    client.set_token_wasm(&BytesN::from_array(&env, &[7; 32]));
+    assert!(client.try_deploy_srwa_token(&salt, &config, &TokenTemplate::RwaEquity).is_err());
+    assert!(client.get_deployed_token(&client.predict_addresses(&salt).0).is_none());
+}
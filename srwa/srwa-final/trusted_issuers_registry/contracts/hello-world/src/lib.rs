@@ -1,10 +1,25 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, symbol_short, Address, Env, Symbol, Vec
+    contract, contractimpl, contracttype, symbol_short, Address, Env, String, Symbol, Vec
 };
 
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const ISSUERS: Symbol = symbol_short!("ISSUERS");
+const TOPIC_ISSUERS: Symbol = symbol_short!("TOPIC_ISS");
+const ISSUER_TOPICS: Symbol = symbol_short!("ISS_TOPIC");
+const PROFILES: Symbol = symbol_short!("PROFILE");
+
+// active_until == 0 leaves the window open-ended. A suspended issuer is
+// untrusted for every topic until reinstated
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct IssuerProfile {
+    pub name: String,
+    pub uri: String,
+    pub active_from: u64,
+    pub active_until: u64,
+    pub suspended: bool,
+}
 
 #[contract]
 pub struct TrustedIssuersRegistry;
@@ -24,6 +39,30 @@ impl TrustedIssuersRegistry {
         let key = (ISSUERS, issuer.clone(), topic_id);
         env.storage().persistent().set(&key, &true);
 
+        let mut issuers = Self::get_trusted_issuers_for_topic(env.clone(), topic_id);
+        if !issuers.contains(&issuer) {
+            issuers.push_back(issuer.clone());
+            env.storage().persistent().set(&(TOPIC_ISSUERS, topic_id), &issuers);
+        }
+
+        let mut topics = Self::get_topics_for_issuer(env.clone(), issuer.clone());
+        if !topics.contains(topic_id) {
+            topics.push_back(topic_id);
+            env.storage().persistent().set(&(ISSUER_TOPICS, issuer.clone()), &topics);
+        }
+
+        let profile_key = (PROFILES, issuer.clone());
+        if !env.storage().persistent().has(&profile_key) {
+            let profile = IssuerProfile {
+                name: String::from_str(&env, ""),
+                uri: String::from_str(&env, ""),
+                active_from: 0,
+                active_until: 0,
+                suspended: false,
+            };
+            env.storage().persistent().set(&profile_key, &profile);
+        }
+
         env.events().publish(
             (symbol_short!("ISS_ADD"),),
             (issuer, topic_id)
@@ -36,6 +75,18 @@ impl TrustedIssuersRegistry {
         let key = (ISSUERS, issuer.clone(), topic_id);
         env.storage().persistent().remove(&key);
 
+        let mut issuers = Self::get_trusted_issuers_for_topic(env.clone(), topic_id);
+        if let Some(index) = issuers.first_index_of(&issuer) {
+            issuers.remove(index);
+            env.storage().persistent().set(&(TOPIC_ISSUERS, topic_id), &issuers);
+        }
+
+        let mut topics = Self::get_topics_for_issuer(env.clone(), issuer.clone());
+        if let Some(index) = topics.first_index_of(topic_id) {
+            topics.remove(index);
+            env.storage().persistent().set(&(ISSUER_TOPICS, issuer.clone()), &topics);
+        }
+
         env.events().publish(
             (symbol_short!("ISS_REM"),),
             (issuer, topic_id)
@@ -43,20 +94,87 @@ impl TrustedIssuersRegistry {
     }
 
     pub fn is_trusted(env: Env, issuer: Address, topic_id: u32) -> bool {
-        let key = (ISSUERS, issuer, topic_id);
-        env.storage().persistent().get(&key).unwrap_or(false)
+        let key = (ISSUERS, issuer.clone(), topic_id);
+        env.storage().persistent().get(&key).unwrap_or(false) && Self::is_issuer_active(env, issuer)
+    }
+
+    pub fn get_trusted_issuers_for_topic(env: Env, topic_id: u32) -> Vec<Address> {
+        let key = (TOPIC_ISSUERS, topic_id);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn get_topics_for_issuer(env: Env, issuer: Address) -> Vec<u32> {
+        let key = (ISSUER_TOPICS, issuer);
+        env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn set_issuer_profile(
+        env: Env,
+        issuer: Address,
+        name: String,
+        uri: String,
+        active_from: u64,
+        active_until: u64,
+    ) {
+        Self::require_admin(&env);
+
+        if active_until != 0 && active_until <= active_from {
+            panic!("Invalid activity window");
+        }
+
+        // Suspension is managed separately and survives profile updates
+        let suspended = Self::get_issuer_profile(env.clone(), issuer.clone())
+            .is_some_and(|profile| profile.suspended);
+        let profile = IssuerProfile {
+            name,
+            uri,
+            active_from,
+            active_until,
+            suspended,
+        };
+        env.storage().persistent().set(&(PROFILES, issuer.clone()), &profile);
+
+        env.events().publish(
+            (symbol_short!("ISS_PROF"),),
+            (issuer, profile)
+        );
+    }
+
+    pub fn get_issuer_profile(env: Env, issuer: Address) -> Option<IssuerProfile> {
+        env.storage().persistent().get(&(PROFILES, issuer))
+    }
+
+    // Every claim the issuer produced fails verification while suspended
+    pub fn suspend_issuer(env: Env, issuer: Address) {
+        Self::require_admin(&env);
+        Self::set_suspended(&env, &issuer, true);
+
+        env.events().publish(
+            (symbol_short!("ISS_SUSP"),),
+            issuer
+        );
     }
 
-    pub fn get_trusted_issuers_for_topic(env: Env, _topic_id: u32) -> Vec<Address> {
-        // Note: In a real implementation, you'd need an index to efficiently query by topic
-        // For now, return empty vector
-        Vec::new(&env)
+    pub fn reinstate_issuer(env: Env, issuer: Address) {
+        Self::require_admin(&env);
+        Self::set_suspended(&env, &issuer, false);
+
+        env.events().publish(
+            (symbol_short!("ISS_REIN"),),
+            issuer
+        );
     }
 
-    pub fn get_topics_for_issuer(env: Env, _issuer: Address) -> Vec<u32> {
-        // Note: In a real implementation, you'd need an index to efficiently query by issuer
-        // For now, return empty vector
-        Vec::new(&env)
+    pub fn is_issuer_active(env: Env, issuer: Address) -> bool {
+        match Self::get_issuer_profile(env.clone(), issuer) {
+            Some(profile) => {
+                let now = env.ledger().timestamp();
+                !profile.suspended
+                    && now >= profile.active_from
+                    && (profile.active_until == 0 || now < profile.active_until)
+            }
+            None => true,
+        }
     }
 
     pub fn set_admin(env: Env, new_admin: Address) {
@@ -68,6 +186,14 @@ impl TrustedIssuersRegistry {
         env.storage().instance().get(&ADMIN).unwrap()
     }
 
+    fn set_suspended(env: &Env, issuer: &Address, suspended: bool) {
+        let key = (PROFILES, issuer.clone());
+        let mut profile: IssuerProfile = env.storage().persistent().get(&key)
+            .unwrap_or_else(|| panic!("Issuer not found"));
+        profile.suspended = suspended;
+        env.storage().persistent().set(&key, &profile);
+    }
+
     fn require_admin(env: &Env) {
         let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
         admin.require_auth();
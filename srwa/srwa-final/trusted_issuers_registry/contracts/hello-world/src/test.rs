@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{testutils::{Address as _, Ledger}, vec, Address, Env, String};
 
 #[test]
 fn test_trusted_issuers_registry() {
@@ -34,4 +34,52 @@ fn test_trusted_issuers_registry() {
 
     // Should no longer be trusted
     assert!(!client.is_trusted(&issuer, &topic_id));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_issuer_indices_and_profiles() {
+    let env = Env::default();
+    let contract_id = env.register(TrustedIssuersRegistry, ());
+    let client = TrustedIssuersRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let kyc_vendor = Address::generate(&env);
+    let aml_vendor = Address::generate(&env);
+
+    client.initialize(&admin);
+    env.mock_all_auths();
+
+    client.add_trusted_issuer(&kyc_vendor, &1u32);
+    client.add_trusted_issuer(&kyc_vendor, &2u32);
+    client.add_trusted_issuer(&aml_vendor, &2u32);
+
+    assert_eq!(client.get_trusted_issuers_for_topic(&2u32), vec![&env, kyc_vendor.clone(), aml_vendor.clone()]);
+    assert_eq!(client.get_topics_for_issuer(&kyc_vendor), vec![&env, 1u32, 2u32]);
+
+    client.remove_trusted_issuer(&kyc_vendor, &2u32);
+    assert_eq!(client.get_trusted_issuers_for_topic(&2u32), vec![&env, aml_vendor.clone()]);
+    assert_eq!(client.get_topics_for_issuer(&kyc_vendor), vec![&env, 1u32]);
+
+    // Suspension distrusts the issuer for every topic and survives profile updates
+    client.suspend_issuer(&kyc_vendor);
+    assert!(!client.is_trusted(&kyc_vendor, &1u32));
+
+    client.set_issuer_profile(
+        &kyc_vendor,
+        &String::from_str(&env, "Acme KYC"),
+        &String::from_str(&env, "did:web:kyc.acme.example"),
+        &0u64,
+        &1000u64,
+    );
+    assert!(client.get_issuer_profile(&kyc_vendor).unwrap().suspended);
+
+    client.reinstate_issuer(&kyc_vendor);
+    assert!(client.is_trusted(&kyc_vendor, &1u32));
+
+    // Trust lapses outside the activity window
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    assert!(!client.is_trusted(&kyc_vendor, &1u32));
+    assert!(client.is_trusted(&aml_vendor, &2u32));
+
+    assert!(client.try_suspend_issuer(&Address::generate(&env)).is_err());
+}